@@ -0,0 +1,235 @@
+//! Benchmarks for generating, texturing and spawning chunks.
+//!
+//! ```sh
+//! cargo bench --bench chunk
+//! ```
+
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_sign_loss)]
+#![allow(clippy::cast_possible_wrap)]
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::noise::NoiseMap;
+use bevy_game::map::tile_registry::TileRegistry;
+use bevy_game::map::world_shape::WorldShape;
+use bevy_game::map::{map_plugin, ChunkBorderState, ChunkLod, ChunkReloadEvent};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use image::{DynamicImage, Rgba};
+use imageproc::drawing::draw_filled_rect_mut;
+use imageproc::rect;
+
+const CHUNK_TILE_COUNTS: [usize; 4] = [10, 20, 50, 100];
+const TILE_SIZES: [f32; 4] = [5.0, 15.0, 50.0, 100.0];
+/// Chunk sizes and tile sizes for full spawns, kept to chunks that fit in memory a screen at a time.
+const SPAWN_SIZES: [(usize, f32); 6] = [
+    (10, 5.0),
+    (10, 15.0),
+    (20, 5.0),
+    (20, 15.0),
+    (50, 5.0),
+    (50, 15.0),
+];
+
+const fn config(chunk_tile_count: usize, tile_size: f32) -> MapConfig {
+    MapConfig {
+        seed: 42,
+        chunk_tile_count,
+        tile_size,
+        pixel_per_tile: false,
+        world_shape: WorldShape::Infinite,
+        heightmap: None,
+        stamps: Vec::new(),
+    }
+}
+
+/// A chunk with a mix of tile kinds, so no shortcuts are taken drawing it.
+fn mixed_chunk(config: &MapConfig, registry: &TileRegistry) -> Chunk {
+    Chunk::new(
+        ChunkPosition { x: 3, y: 0 },
+        &NoiseMap::new(config),
+        config,
+        registry,
+    )
+}
+
+fn uniform_chunk(config: &MapConfig, registry: &TileRegistry) -> Chunk {
+    let deep_water = registry
+        .find("deep_water")
+        .expect("The default tiles should include deep water");
+    Chunk::filled(config.chunk_tile_count, deep_water)
+}
+
+fn chunk_new(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Chunk::new");
+    let registry = TileRegistry::default();
+
+    for chunk_tile_count in CHUNK_TILE_COUNTS {
+        let config = config(chunk_tile_count, 15.0);
+        let noisemap = NoiseMap::new(&config);
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(chunk_tile_count),
+            &config,
+            |b, config| {
+                b.iter(|| Chunk::new(ChunkPosition { x: 3, y: 0 }, &noisemap, config, &registry));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn generate_texture_image(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Chunk::generate_texture_image");
+    let registry = TileRegistry::default();
+
+    for tile_size in TILE_SIZES {
+        let config = config(20, tile_size);
+
+        for (kind, chunk) in [
+            ("mixed", mixed_chunk(&config, &registry)),
+            ("uniform", uniform_chunk(&config, &registry)),
+        ] {
+            for chunk_borders in [ChunkBorderState::Hidden, ChunkBorderState::Shown] {
+                let borders = if chunk_borders.into() {
+                    "borders"
+                } else {
+                    "no borders"
+                };
+
+                group.bench_function(
+                    BenchmarkId::new(format!("{kind}, {borders}"), tile_size),
+                    |b| {
+                        b.iter(|| {
+                            chunk.generate_texture_image(
+                                &config,
+                                &registry,
+                                chunk_borders,
+                                ChunkLod::default(),
+                            )
+                        });
+                    },
+                );
+            }
+        }
+    }
+
+    group.finish();
+}
+
+/// The original texture renderer, drawing a rectangle per tile into an RGB image
+/// before converting it to RGBA. Kept to compare against [`Chunk::generate_texture_image`].
+fn imageproc_texture_image(chunk: &Chunk, config: &MapConfig, registry: &TileRegistry) -> Image {
+    let tile_size = config.tile_size as u32;
+    let chunk_size = config.chunk_tile_count as u32 * tile_size;
+
+    let mut dyn_image = DynamicImage::new_rgb8(chunk_size, chunk_size);
+
+    for (local, tile) in chunk.iter() {
+        // Image rows go top to bottom, while chunk rows go bottom to top.
+        let row = chunk.size() as u32 - 1 - local.y;
+        draw_filled_rect_mut(
+            &mut dyn_image,
+            rect::Rect::at((local.x * tile_size) as i32, (row * tile_size) as i32)
+                .of_size(tile_size, tile_size),
+            Rgba(registry.color(tile).as_rgba_u8()),
+        );
+    }
+
+    Image::from_dynamic(dyn_image, true, RenderAssetUsages::RENDER_WORLD)
+}
+
+/// Compares texture renderers on a mixed chunk with large tiles.
+fn texture_renderers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("texture renderers");
+
+    let config = config(20, 100.0);
+    let pixel_per_tile_config = MapConfig {
+        pixel_per_tile: true,
+        ..config.clone()
+    };
+    let registry = TileRegistry::default();
+    let chunk = mixed_chunk(&config, &registry);
+
+    group.bench_function("imageproc", |b| {
+        b.iter(|| imageproc_texture_image(&chunk, &config, &registry));
+    });
+    group.bench_function("direct", |b| {
+        b.iter(|| {
+            chunk.generate_texture_image(
+                &config,
+                &registry,
+                ChunkBorderState::Hidden,
+                ChunkLod::default(),
+            )
+        });
+    });
+    group.bench_function("pixel per tile", |b| {
+        b.iter(|| {
+            chunk.generate_texture_image(
+                &pixel_per_tile_config,
+                &registry,
+                ChunkBorderState::Hidden,
+                ChunkLod::default(),
+            )
+        });
+    });
+
+    group.finish();
+}
+
+/// Builds a headless app with a camera looking at the map.
+fn map_app(config: MapConfig) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), map_plugin))
+        .init_asset::<Image>()
+        .insert_resource(config);
+
+    let half_size = Vec2::new(1280.0, 720.0) / 2.0;
+    app.world.spawn((
+        Camera2dBundle::default().camera,
+        Transform::default(),
+        OrthographicProjection {
+            area: Rect::from_corners(-half_size, half_size),
+            ..default()
+        },
+    ));
+
+    app.update();
+    app
+}
+
+/// A full reload, generating and spawning every chunk on screen.
+fn spawn_chunks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn_chunks");
+    group.sample_size(20);
+
+    for (chunk_tile_count, tile_size) in SPAWN_SIZES {
+        let mut app = map_app(config(chunk_tile_count, tile_size));
+
+        group.bench_function(
+            BenchmarkId::new(format!("{chunk_tile_count} tiles"), tile_size),
+            |b| {
+                b.iter(|| {
+                    app.world.send_event(ChunkReloadEvent);
+                    app.update();
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    chunk_new,
+    generate_texture_image,
+    texture_renderers,
+    spawn_chunks
+);
+criterion_main!(benches);
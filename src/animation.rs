@@ -0,0 +1,275 @@
+//! Frame by frame sprite animation, with clips cut from a sprite sheet described by an `.anim.ron` file.
+//!
+//! ```ron
+//! (
+//!     texture:    "walker.png",
+//!     frame_size: (16, 16),
+//!     columns:    4,
+//!     rows:       2,
+//!     clips:      {
+//!         "idle_right": (frames: [0, 1], fps: 2.0),
+//!         "walk_right": (frames: [4, 5, 6, 7], fps: 8.0),
+//!         "swim_right": (frames: [2, 3], fps: 4.0, mode: PingPong),
+//!     },
+//! )
+//! ```
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, LoadState};
+use bevy::prelude::*;
+use bevy::utils::{BoxedFuture, HashMap};
+use serde::Deserialize;
+
+pub fn animation_plugin(app: &mut App) {
+    app.init_asset::<AnimationSheet>()
+        .init_asset_loader::<AnimationSheetLoader>()
+        .add_systems(PostUpdate, animate_sprites);
+}
+
+/// How a clip carries on once it reaches its last frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum LoopMode {
+    /// Start again from the first frame.
+    #[default]
+    Loop,
+    /// Stay on the last frame.
+    Once,
+    /// Play backwards to the first frame, then forwards again.
+    PingPong,
+}
+
+/// A named run of frames in an [`AnimationSheet`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SpriteClip {
+    /// Frames of the sheet, counted left to right then top to bottom.
+    pub frames: Vec<usize>,
+    /// How many frames are shown each second.
+    pub fps:    f32,
+    #[serde(default)]
+    pub mode:   LoopMode,
+}
+
+impl SpriteClip {
+    /// Position in [`frames`](Self::frames) shown `elapsed` seconds into the clip.
+    pub fn frame_at(&self, elapsed: f32) -> usize {
+        let count = self.frames.len();
+        let step = (elapsed.max(0.0) * self.fps) as usize;
+
+        match self.mode {
+            LoopMode::Loop => step % count,
+            LoopMode::Once => step.min(count - 1),
+            LoopMode::PingPong if count > 1 => {
+                let cycle = 2 * count - 2;
+                let step = step % cycle;
+                if step < count {
+                    step
+                } else {
+                    cycle - step
+                }
+            }
+            LoopMode::PingPong => 0,
+        }
+    }
+
+    /// Seconds before the clip repeats itself, `None` if it stops on its last frame.
+    pub fn cycle_duration(&self) -> Option<f32> {
+        let frames = match self.mode {
+            LoopMode::Loop => self.frames.len(),
+            LoopMode::Once => return None,
+            LoopMode::PingPong => (2 * self.frames.len()).saturating_sub(2).max(1),
+        };
+        Some(frames as f32 / self.fps)
+    }
+}
+
+/// A sprite sheet split into equally sized frames, and the clips played from it.
+#[derive(Asset, TypePath, Debug)]
+pub struct AnimationSheet {
+    pub texture: Handle<Image>,
+    pub layout:  Handle<TextureAtlasLayout>,
+    pub clips:   HashMap<String, SpriteClip>,
+}
+
+/// An animation sheet as written in an `.anim.ron` file.
+#[derive(Debug, Deserialize)]
+pub struct AnimationSheetData {
+    /// Sprite sheet image, relative to the `.anim.ron` file.
+    pub texture:    String,
+    /// Size of each frame in pixels.
+    pub frame_size: UVec2,
+    pub columns:    usize,
+    pub rows:       usize,
+    pub clips:      HashMap<String, SpriteClip>,
+}
+
+impl AnimationSheetData {
+    /// Parses the contents of an `.anim.ron` file.
+    ///
+    /// # Errors
+    ///
+    /// If the data is invalid, or a clip has no frames, frames outside the sheet or no frame rate.
+    pub fn from_ron(data: &str) -> Result<Self, String> {
+        let sheet: Self = ron::from_str(data).map_err(|e| e.to_string())?;
+
+        let frame_count = sheet.columns * sheet.rows;
+        for (name, clip) in &sheet.clips {
+            if clip.frames.is_empty() {
+                return Err(format!("clip `{name}` has no frames"));
+            }
+            if let Some(frame) = clip.frames.iter().find(|&&frame| frame >= frame_count) {
+                return Err(format!(
+                    "clip `{name}` uses frame {frame}, but the sheet only has {frame_count} frames"
+                ));
+            }
+            if clip.fps <= 0.0 || !clip.fps.is_finite() {
+                return Err(format!("clip `{name}` has a frame rate of {}", clip.fps));
+            }
+        }
+
+        Ok(sheet)
+    }
+}
+
+/// Loads [`AnimationSheet`]s from `.anim.ron` files, along with their sprite sheet image.
+#[derive(Default)]
+struct AnimationSheetLoader;
+
+impl AssetLoader for AnimationSheetLoader {
+    type Asset = AnimationSheet;
+    type Settings = ();
+    type Error = String;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<AnimationSheet, String>> {
+        Box::pin(async move {
+            let mut data = String::new();
+            reader
+                .read_to_string(&mut data)
+                .await
+                .map_err(|e| e.to_string())?;
+            let sheet = AnimationSheetData::from_ron(&data)?;
+
+            let texture = load_context.load(load_context.path().with_file_name(&sheet.texture));
+            let layout = load_context.add_labeled_asset(
+                "layout".to_string(),
+                TextureAtlasLayout::from_grid(
+                    sheet.frame_size.as_vec2(),
+                    sheet.columns,
+                    sheet.rows,
+                    None,
+                    None,
+                ),
+            );
+
+            Ok(AnimationSheet {
+                texture,
+                layout,
+                clips: sheet.clips,
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] { &["anim.ron"] }
+}
+
+/// Plays clips from an [`AnimationSheet`] on the entities [`TextureAtlas`].
+///
+/// The entity is given the sheets image and layout once it has loaded.
+#[derive(Component, Debug, Clone)]
+pub struct SpriteAnimation {
+    pub sheet: Handle<AnimationSheet>,
+    clip:      String,
+    elapsed:   f32,
+}
+
+impl SpriteAnimation {
+    pub const fn new(sheet: Handle<AnimationSheet>) -> Self {
+        Self {
+            sheet,
+            clip: String::new(),
+            elapsed: 0.0,
+        }
+    }
+
+    /// Name of the clip being played.
+    pub fn clip(&self) -> &str { &self.clip }
+
+    /// Seconds the current clip has been playing for.
+    pub const fn elapsed(&self) -> f32 { self.elapsed }
+
+    /// Plays `clip` from its first frame, unless it is already playing.
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            clip.clone_into(&mut self.clip);
+            self.elapsed = 0.0;
+        }
+    }
+
+    /// Plays `clip` from the same point the current clip is at, such as when turning mid stride.
+    pub fn switch(&mut self, clip: &str) {
+        if self.clip != clip {
+            clip.clone_into(&mut self.clip);
+        }
+    }
+}
+
+/// Advance every [`SpriteAnimation`] and show the frame its clip is on.
+///
+/// Sprites are drawn with their own image until the sheet and its image have loaded.
+/// If either fails to load the animation is removed, so the sprite keeps its own image.
+pub fn animate_sprites(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    sheets: Res<Assets<AnimationSheet>>,
+    mut sprites: Query<(
+        Entity,
+        &mut SpriteAnimation,
+        &mut TextureAtlas,
+        &mut Handle<Image>,
+    )>,
+) {
+    for (entity, mut animation, mut atlas, mut texture) in &mut sprites {
+        let sheet = sheets.get(&animation.sheet);
+        let load_state = match sheet {
+            Some(sheet) => asset_server.load_state(&sheet.texture),
+            None => asset_server.load_state(&animation.sheet),
+        };
+
+        let sheet = match (sheet, load_state) {
+            (_, LoadState::Failed) => {
+                commands
+                    .entity(entity)
+                    .remove::<(SpriteAnimation, TextureAtlas)>();
+                continue;
+            }
+            // Images that weren't loaded by the asset server, such as ones added directly, are ready to use.
+            (Some(sheet), LoadState::Loaded | LoadState::NotLoaded) => sheet,
+            _ => continue,
+        };
+
+        if atlas.layout != sheet.layout {
+            atlas.layout = sheet.layout.clone();
+            *texture = sheet.texture.clone();
+        }
+
+        let Some(clip) = sheet.clips.get(&animation.clip) else {
+            continue;
+        };
+
+        animation.elapsed += time.delta_seconds();
+        // Kept within a cycle so long running clips don't lose precision.
+        if let Some(cycle) = clip.cycle_duration() {
+            animation.elapsed %= cycle;
+        }
+
+        let index = clip.frames[clip.frame_at(animation.elapsed)];
+        if atlas.index != index {
+            atlas.index = index;
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Exports a region of a world as a Tiled map, to hand edit and bring back as a stamp.
+//!
+//! ```sh
+//! cargo run --bin bevy_game-export -- --seed 42 --from -1,-1 --to 1,1 -o town.tmx
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::region::{region_tiled_map, ChunkRect};
+use bevy_game::map::tile_registry::{TileRegistry, TILES_PATH};
+use bevy_game::map::tiled::tileset_image;
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(about = "Export a region of a world as a Tiled map")]
+struct Args {
+    /// The world seed.
+    #[arg(long)]
+    seed:             u64,
+    /// The amount of tiles in a chunk.
+    #[arg(long, default_value_t = MapConfig::default().chunk_tile_count)]
+    chunk_tile_count: usize,
+    /// One corner of the region, as chunk coordinates `x,y`.
+    #[arg(long, allow_hyphen_values = true)]
+    from:             ChunkPosition,
+    /// The opposite corner of the region, as chunk coordinates `x,y`.
+    #[arg(long, allow_hyphen_values = true)]
+    to:               ChunkPosition,
+    /// The tiles file the world is generated with.
+    #[arg(long, default_value = TILES_PATH)]
+    tiles:            PathBuf,
+    /// Size of the tiles in the tileset image, in pixels.
+    #[arg(long, default_value_t = 16)]
+    tile_pixels:      u32,
+    /// Where to write the `.tmx` map, the tileset image is written next to it.
+    #[arg(short, long)]
+    output:           PathBuf,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let config = MapConfig {
+        chunk_tile_count: args.chunk_tile_count,
+        seed: args.seed,
+        ..MapConfig::default()
+    };
+    let rect = ChunkRect::new(args.from, args.to);
+
+    let registry = match TileRegistry::load(&args.tiles) {
+        Ok(registry) => registry,
+        Err(e) => {
+            eprintln!("Failed to load tiles from {}: {e}", args.tiles.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let stem = args
+        .output
+        .file_stem()
+        .map_or_else(|| "map".into(), |stem| stem.to_string_lossy());
+    let tileset_name = format!("{stem}_tiles.png");
+    let tileset_path = args.output.with_file_name(&tileset_name);
+
+    if let Err(e) = tileset_image(&registry, args.tile_pixels).save(&tileset_path) {
+        eprintln!("Failed to write {}: {e}", tileset_path.display());
+        return ExitCode::FAILURE;
+    }
+
+    let map = region_tiled_map(&config, &registry, rect);
+    let tmx = map.to_tmx(&registry, &tileset_name, args.tile_pixels);
+
+    match fs::write(&args.output, tmx) {
+        Ok(()) => {
+            println!(
+                "Wrote {}x{} chunks to {}, stamp it at {},{} to bring it back",
+                rect.width(),
+                rect.height(),
+                args.output.display(),
+                rect.min.x,
+                rect.min.y
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to write {}: {e}", args.output.display());
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Renders a region of a world to a PNG, without needing a window or GPU.
+//!
+//! ```sh
+//! cargo run --bin bevy_game-render -- --seed 42 --from -5,-5 --to 5,5 -o preview.png
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::heightmap::{Heightmap, HeightmapTerrain, OutsideHeightmap};
+use bevy_game::map::region::{render_region, ChunkRect};
+use bevy_game::map::tile_registry::{TileRegistry, TILES_PATH};
+use bevy_game::map::ChunkBorderState;
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(about = "Render a region of a world to a PNG")]
+struct Args {
+    /// The world seed.
+    #[arg(long)]
+    seed:             u64,
+    /// Size of a tile in pixels.
+    #[arg(long, default_value_t = MapConfig::default().tile_size)]
+    tile_size:        f32,
+    /// The amount of tiles in a chunk.
+    #[arg(long, default_value_t = MapConfig::default().chunk_tile_count)]
+    chunk_tile_count: usize,
+    /// One corner of the region, as chunk coordinates `x,y`.
+    #[arg(long, allow_hyphen_values = true)]
+    from:             ChunkPosition,
+    /// The opposite corner of the region, as chunk coordinates `x,y`.
+    #[arg(long, allow_hyphen_values = true)]
+    to:               ChunkPosition,
+    /// The tiles file the world is generated and drawn with.
+    #[arg(long, default_value = TILES_PATH)]
+    tiles:            PathBuf,
+    /// A greyscale image to use as the terrain, centred on the origin.
+    #[arg(long)]
+    heightmap:        Option<PathBuf>,
+    /// Only ocean outside of `--heightmap`, rather than procedural terrain.
+    #[arg(long, requires = "heightmap")]
+    heightmap_only:   bool,
+    /// Draw chunk borders.
+    #[arg(long)]
+    borders:          bool,
+    /// Where to write the PNG.
+    #[arg(short, long)]
+    output:           PathBuf,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let heightmap = match args.heightmap.as_deref().map(Heightmap::load).transpose() {
+        Ok(heightmap) => heightmap,
+        Err(e) => {
+            eprintln!("Failed to load heightmap: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let config = MapConfig {
+        tile_size: args.tile_size,
+        chunk_tile_count: args.chunk_tile_count,
+        seed: args.seed,
+        heightmap: heightmap.map(|heightmap| HeightmapTerrain {
+            heightmap: Arc::new(heightmap),
+            outside:   if args.heightmap_only {
+                OutsideHeightmap::Ocean
+            } else {
+                OutsideHeightmap::Procedural
+            },
+        }),
+        ..MapConfig::default()
+    };
+    let rect = ChunkRect::new(args.from, args.to);
+    let chunk_borders = if args.borders {
+        ChunkBorderState::Shown
+    } else {
+        ChunkBorderState::Hidden
+    };
+
+    let registry = match TileRegistry::load(&args.tiles) {
+        Ok(registry) => registry,
+        Err(e) => {
+            eprintln!("Failed to load tiles from {}: {e}", args.tiles.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let image = render_region(&config, &registry, rect, chunk_borders);
+
+    match image.save(&args.output) {
+        Ok(()) => {
+            println!(
+                "Wrote {}x{} chunks to {}",
+                rect.width(),
+                rect.height(),
+                args.output.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to write {}: {e}", args.output.display());
+            ExitCode::FAILURE
+        }
+    }
+}
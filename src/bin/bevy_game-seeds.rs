@@ -0,0 +1,187 @@
+//! Searches many seeds in parallel for worlds that match some criteria.
+//!
+//! ```sh
+//! cargo run --release --bin bevy_game-seeds -- --count 10000 --land-at-origin --min-land-ratio 0.5 --within 2
+//! ```
+
+#![allow(clippy::cast_precision_loss)]
+#![allow(clippy::cast_possible_wrap)]
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use bevy::math::IVec2;
+use bevy::utils::HashMap;
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::noise::NoiseMap;
+use bevy_game::map::tile_registry::{TileId, TileRegistry, TILES_PATH};
+use clap::Parser;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+#[derive(Parser)]
+#[command(about = "Search seeds for worlds that match the given constraints")]
+struct Args {
+    /// The first seed to check.
+    #[arg(long, default_value_t = 0)]
+    start:            u64,
+    /// The amount of seeds to check.
+    #[arg(long, default_value_t = 1000)]
+    count:            u64,
+    /// The amount of tiles in a chunk.
+    #[arg(long, default_value_t = MapConfig::default().chunk_tile_count)]
+    chunk_tile_count: usize,
+    /// The tiles file the world is generated with.
+    #[arg(long, default_value = TILES_PATH)]
+    tiles:            PathBuf,
+    /// Require the tile at the origin to be land.
+    #[arg(long)]
+    land_at_origin:   bool,
+    /// Require at least this ratio of land tiles, between 0 and 1, within `--within` chunks of the origin.
+    #[arg(long, requires = "within")]
+    min_land_ratio:   Option<f32>,
+    /// Distance in chunks from the origin chunk used for `--min-land-ratio`.
+    #[arg(long)]
+    within:           Option<u32>,
+    /// Require a tile with this name within `--radius` tiles of the origin.
+    #[arg(long, requires = "radius")]
+    tile:             Option<String>,
+    /// Distance in tiles from the origin used for `--tile`.
+    #[arg(long)]
+    radius:           Option<u32>,
+}
+
+/// Lazily generates the chunks of a single world.
+struct World<'a> {
+    config:   MapConfig,
+    registry: &'a TileRegistry,
+    noisemap: NoiseMap,
+    chunks:   HashMap<ChunkPosition, Chunk>,
+}
+
+impl<'a> World<'a> {
+    fn new(config: MapConfig, registry: &'a TileRegistry) -> Self {
+        Self {
+            noisemap: NoiseMap::new(&config),
+            config,
+            registry,
+            chunks: HashMap::new(),
+        }
+    }
+
+    fn chunk(&mut self, position: ChunkPosition) -> &Chunk {
+        self.chunks
+            .entry(position)
+            .or_insert_with(|| Chunk::new(position, &self.noisemap, &self.config, self.registry))
+    }
+
+    fn tile(&mut self, tile: IVec2) -> TileId {
+        let (position, local) = ChunkPosition::from_tile(tile, &self.config);
+        self.chunk(position)
+            .get(local)
+            .expect("Local tile coordinates should be within the chunk")
+    }
+
+    fn is_land(&self, tile: TileId) -> bool {
+        self.registry.get(tile).is_some_and(|tile| tile.land)
+    }
+}
+
+impl Args {
+    /// Checks every constraint, cheapest first.
+    fn matches(&self, seed: u64, registry: &TileRegistry, tile: Option<TileId>) -> bool {
+        let mut world = World::new(
+            MapConfig {
+                seed,
+                chunk_tile_count: self.chunk_tile_count,
+                ..MapConfig::default()
+            },
+            registry,
+        );
+
+        if self.land_at_origin {
+            let origin = world.tile(IVec2::ZERO);
+            if !world.is_land(origin) {
+                return false;
+            }
+        }
+
+        if let (Some(kind), Some(radius)) = (tile, self.radius) {
+            let radius = radius as i32;
+            let found = (-radius..=radius)
+                .flat_map(|x| (-radius..=radius).map(move |y| IVec2::new(x, y)))
+                .filter(|tile| tile.length_squared() <= radius * radius)
+                .any(|tile| world.tile(tile) == kind);
+
+            if !found {
+                return false;
+            }
+        }
+
+        if let (Some(min_land_ratio), Some(within)) = (self.min_land_ratio, self.within) {
+            let origin = ChunkPosition::from_tile(IVec2::ZERO, &world.config).0;
+            let within = within as i32;
+
+            let (mut land, mut total) = (0_usize, 0_usize);
+            for x in origin.x - within..=origin.x + within {
+                for y in origin.y - within..=origin.y + within {
+                    let chunk = world.chunk(ChunkPosition { x, y });
+                    land += chunk
+                        .iter()
+                        .filter_map(|(_, tile)| registry.get(tile))
+                        .filter(|tile| tile.land)
+                        .count();
+                    total += chunk.size() * chunk.size();
+                }
+            }
+
+            if (land as f32 / total as f32) < min_land_ratio {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let registry = match TileRegistry::load(&args.tiles) {
+        Ok(registry) => registry,
+        Err(e) => {
+            eprintln!("Failed to load tiles from {}: {e}", args.tiles.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let tile = match args.tile.as_deref().map(|name| (name, registry.find(name))) {
+        None => None,
+        Some((_, Some(tile))) => Some(tile),
+        Some((name, None)) => {
+            let names: Vec<_> = registry
+                .iter()
+                .map(|(_, tile)| tile.name.as_str())
+                .collect();
+            eprintln!(
+                "Unknown tile `{name}`, expected one of: {}",
+                names.join(", ")
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut seeds: Vec<u64> = (args.start..args.start.saturating_add(args.count))
+        .into_par_iter()
+        .filter(|seed| args.matches(*seed, &registry, tile))
+        .collect();
+    seeds.sort_unstable();
+
+    for seed in &seeds {
+        println!("{seed}");
+    }
+
+    eprintln!("{} of {} seeds matched", seeds.len(), args.count);
+    ExitCode::SUCCESS
+}
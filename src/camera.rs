@@ -1,28 +1,353 @@
 //! Camera and zoom functionality.
 
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
+use bevy::render::camera::CameraUpdateSystem;
+use bevy::transform::TransformSystem;
+use bevy::window::PrimaryWindow;
 
-use crate::get_single_mut;
+use crate::input::{Action, UiFocus};
+use crate::map::config::MapConfig;
+use crate::map::ChunkLod;
+use crate::player::{sprite_movement, Player};
+use crate::world_map::WorldMapState;
+use crate::{get_single, get_single_mut};
 
 pub fn camera_plugin(app: &mut App) {
-    app.add_systems(Startup, setup)
-        .add_systems(Update, update_zoom);
+    app.init_resource::<CameraFollow>()
+        .init_resource::<CameraZoom>()
+        .init_resource::<PixelPerfect>()
+        .init_resource::<PixelSnap>()
+        .init_state::<CameraMode>()
+        .add_systems(Startup, setup)
+        .add_systems(First, unsnap_camera)
+        .add_systems(
+            Update,
+            (
+                toggle_free_camera,
+                // The world map uses the mouse wheel for itself.
+                zoom_input.run_if(in_state(WorldMapState::Closed)),
+                update_zoom,
+                follow_player.run_if(in_state(CameraMode::Follow)),
+                pan_camera.run_if(in_state(CameraMode::Free)),
+                clamp_camera,
+            )
+                .chain()
+                .after(sprite_movement),
+        )
+        .add_systems(
+            PostUpdate,
+            snap_camera
+                .before(CameraUpdateSystem)
+                .before(TransformSystem::TransformPropagate),
+        );
 }
 
+/// How much one line of mouse wheel scrolling zooms the camera.
+const SCROLL_ZOOM_FACTOR: f32 = 1.1;
+
+/// Whether the camera follows the player or is moved around freely to look at the terrain.
+#[derive(States, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CameraMode {
+    #[default]
+    Follow,
+    /// Dragging with the middle mouse button pans the camera.
+    Free,
+}
+
+/// The scale the camera is zooming to and its limits.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct CameraZoom {
+    /// The [`OrthographicProjection::scale`] being eased towards.
+    pub target: f32,
+    pub min:    f32,
+    pub max:    f32,
+    /// How quickly the scale reaches the target, higher is snappier. Zero zooms instantly.
+    pub easing: f32,
+    /// Where on screen to zoom around, relative to its centre with up being positive.
+    /// `None` zooms around the centre.
+    ///
+    /// Only used by the free camera. The following camera always zooms around its centre,
+    /// as it would be pulled straight back to the player.
+    pub anchor: Option<Vec2>,
+}
+
+impl Default for CameraZoom {
+    fn default() -> Self {
+        Self {
+            target: 1.0,
+            min:    0.25,
+            max:    16.0,
+            easing: 12.0,
+            anchor: None,
+        }
+    }
+}
+
+/// How the camera follows the [`Player`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct CameraFollow {
+    /// How quickly the camera catches up, higher is snappier. Zero locks it to where it's heading.
+    pub smoothing: f32,
+    /// Half the size of the area in the middle of the screen that the player can move around in
+    /// without the camera following, in world units.
+    pub deadzone:  Vec2,
+    /// How far ahead of the player the camera looks in the direction they're moving, in world units.
+    pub lookahead: f32,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            smoothing: 6.0,
+            deadzone:  Vec2::new(60.0, 40.0),
+            lookahead: 100.0,
+        }
+    }
+}
+
+/// Keeps tiles crisp by lining texels up with screen pixels, so their edges don't shimmer.
+///
+/// Zoom eases to a whole number of screen pixels per texel, or texels per pixel when zoomed far out,
+/// and the camera is drawn at the closest whole pixel to where it is.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PixelPerfect(pub bool);
+
+/// How far [`snap_camera`] moved the camera this frame, so it can be moved back before anything else uses it.
+#[derive(Resource, Default)]
+struct PixelSnap(Vec3);
+
 fn setup(mut commands: Commands) { commands.spawn(Camera2dBundle::default()); }
 
+fn toggle_free_camera(
+    actions: Res<ButtonInput<Action>>,
+    mode: Res<State<CameraMode>>,
+    mut next_mode: ResMut<NextState<CameraMode>>,
+) {
+    if actions.just_pressed(Action::ToggleFreeCamera) {
+        next_mode.set(match mode.get() {
+            CameraMode::Follow => CameraMode::Free,
+            CameraMode::Free => CameraMode::Follow,
+        });
+    }
+}
+
+/// Change the target zoom with the zoom actions, or the mouse wheel to zoom towards the cursor.
+fn zoom_input(
+    time: Res<Time>,
+    actions: Res<ButtonInput<Action>>,
+    focus: Res<UiFocus>,
+    mut ev_mouse_wheel: EventReader<MouseWheel>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut zoom: ResMut<CameraZoom>,
+) {
+    // Zoom relative to the current scale so zooming far out doesnt take forever.
+    let mut factor = 1.0;
+    let key_zoom = actions.any_pressed([Action::ZoomIn, Action::ZoomOut]);
+    if key_zoom {
+        if actions.pressed(Action::ZoomIn) {
+            factor *= (-time.delta_seconds()).exp();
+        }
+        if actions.pressed(Action::ZoomOut) {
+            factor *= time.delta_seconds().exp();
+        }
+        zoom.anchor = None;
+    }
+
+    let lines: f32 = ev_mouse_wheel
+        .read()
+        .map(|wheel| match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / 20.0,
+        })
+        .sum();
+    // Scrolling over egui windows scrolls them instead.
+    let scrolled = lines.abs() > 0.0 && !focus.pointer;
+    if scrolled {
+        factor *= SCROLL_ZOOM_FACTOR.powf(-lines);
+        zoom.anchor = window.get_single().ok().and_then(|window| {
+            let cursor = window.cursor_position()?;
+            let centre = Vec2::new(window.width(), window.height()) / 2.0;
+            Some(Vec2::new(cursor.x - centre.x, centre.y - cursor.y))
+        });
+    }
+
+    if key_zoom || scrolled {
+        zoom.target = (zoom.target * factor).clamp(zoom.min, zoom.max);
+    }
+}
+
+/// Ease the camera scale towards the [`CameraZoom::target`],
+/// moving the free camera so the world under the anchor stays in place.
 fn update_zoom(
     time: Res<Time>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut projection: Query<&mut OrthographicProjection, With<Camera>>,
+    zoom: Res<CameraZoom>,
+    mode: Res<State<CameraMode>>,
+    config: Res<MapConfig>,
+    pixel_perfect: Res<PixelPerfect>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    let (mut transform, mut projection) = get_single_mut!(camera);
+
+    let scale = projection.scale;
+    let mut target = zoom.target.clamp(zoom.min, zoom.max);
+    if pixel_perfect.0 {
+        let scale_factor = window.get_single().map_or(1.0, Window::scale_factor);
+        // Snapping can cross into another level of detail with differently sized texels.
+        for _ in 0..3 {
+            let snapped = pixel_perfect_scale(target, texel_size(target, &config) * scale_factor);
+            let same_lod = ChunkLod::from_scale(snapped) == ChunkLod::from_scale(target);
+            target = snapped;
+            if same_lod {
+                break;
+            }
+        }
+    }
+    if (scale - target).abs() < f32::EPSILON {
+        return;
+    }
+
+    // Eased in log space, so zooming in and out feel the same.
+    let catch_up = if zoom.easing > 0.0 {
+        1.0 - (-zoom.easing * time.delta_seconds()).exp()
+    } else {
+        1.0
+    };
+    let mut new_scale = scale.ln().lerp(target.ln(), catch_up).exp();
+    if (new_scale - target).abs() < target * 1e-4 {
+        new_scale = target;
+    }
+
+    if let (Some(anchor), CameraMode::Free) = (zoom.anchor, mode.get()) {
+        transform.translation += (anchor * (scale - new_scale)).extend(0.0);
+    }
+    projection.scale = new_scale;
+}
+
+/// Size of a texel of the chunk textures in world units, at the level of detail used for `scale`.
+pub fn texel_size(scale: f32, config: &MapConfig) -> f32 {
+    config.tile_size / ChunkLod::from_scale(scale).tile_pixels(config) as f32
+}
+
+/// The closest scale to `scale` that shows a whole number of pixels per texel,
+/// or texels per pixel when zoomed out far enough that texels are smaller than pixels.
+///
+/// `texel` is the size of a texel in physical pixels at a scale of one.
+pub fn pixel_perfect_scale(scale: f32, texel: f32) -> f32 {
+    let pixels_per_texel = texel / scale;
+
+    if pixels_per_texel >= 1.0 {
+        texel / pixels_per_texel.round()
+    } else {
+        texel * pixels_per_texel.recip().round()
+    }
+}
+
+/// Move the camera to the closest whole physical pixel before it is drawn, when [`PixelPerfect`].
+fn snap_camera(
+    pixel_perfect: Res<PixelPerfect>,
+    mut snap: ResMut<PixelSnap>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut camera: Query<(&mut Transform, &OrthographicProjection), With<Camera>>,
+) {
+    if !pixel_perfect.0 {
+        return;
+    }
+
+    let window = get_single!(window);
+    let (mut transform, projection) = get_single_mut!(camera);
+
+    let pixel = projection.scale / window.scale_factor();
+    // The centre of windows an odd amount of pixels across is in the middle of a pixel.
+    let centre = Vec2::new(
+        (window.physical_width() % 2) as f32,
+        (window.physical_height() % 2) as f32,
+    ) * pixel
+        / 2.0;
+
+    let position = transform.translation.truncate();
+    let snapped = ((position - centre) / pixel).round() * pixel + centre;
+
+    snap.0 = (snapped - position).extend(0.0);
+    transform.translation += snap.0;
+}
+
+/// Undo [`snap_camera`], so the camera keeps moving smoothly rather than getting stuck on whole pixels.
+fn unsnap_camera(mut snap: ResMut<PixelSnap>, mut camera: Query<&mut Transform, With<Camera>>) {
+    if snap.0 == Vec3::ZERO {
+        return;
+    }
+
+    let mut transform = get_single_mut!(camera);
+    transform.translation -= snap.0;
+    snap.0 = Vec3::ZERO;
+}
+
+/// Pan the free camera by dragging with the middle mouse button.
+fn pan_camera(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    focus: Res<UiFocus>,
+    mut ev_mouse_motion: EventReader<MouseMotion>,
+    mut camera: Query<(&mut Transform, &OrthographicProjection), With<Camera>>,
+) {
+    let dragged: Vec2 = ev_mouse_motion.read().map(|motion| motion.delta).sum();
+    if !mouse_input.pressed(MouseButton::Middle) || focus.pointer || dragged == Vec2::ZERO {
+        return;
+    }
+
+    let (mut transform, projection) = get_single_mut!(camera);
+
+    // Screen y goes down while world y goes up.
+    transform.translation += Vec3::new(-dragged.x, dragged.y, 0.0) * projection.scale;
+}
+
+/// Ease the camera towards a point ahead of the player, once they leave the deadzone.
+///
+/// When the player wraps around the world the camera jumps with them, so the teleport can't be seen.
+pub fn follow_player(
+    time: Res<Time>,
+    config: Res<MapConfig>,
+    follow: Res<CameraFollow>,
+    player: Query<&Transform, With<Player>>,
+    mut camera: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+    mut last_player_position: Local<Option<Vec2>>,
 ) {
-    let mut projection = get_single_mut!(projection);
+    let player = get_single!(player).translation.truncate();
+    let mut transform = get_single_mut!(camera);
+
+    let shape = config.world_shape;
+    let camera = shape.nearest(transform.translation.truncate(), player, &config);
+
+    let moved = last_player_position.map_or(Vec2::ZERO, |last| {
+        player - shape.nearest(last, player, &config)
+    });
+    *last_player_position = Some(player);
+
+    let focus = player + moved.normalize_or_zero() * follow.lookahead;
+    let target = focus - (focus - camera).clamp(-follow.deadzone, follow.deadzone);
 
-    if projection.scale >= 0.25 && keyboard_input.pressed(KeyCode::Equal) {
-        projection.scale -= 1.0 * time.delta_seconds();
+    // Exponential smoothing, so the camera eases the same way whatever the frame rate.
+    let catch_up = if follow.smoothing > 0.0 {
+        1.0 - (-follow.smoothing * time.delta_seconds()).exp()
+    } else {
+        1.0
+    };
+    let position = camera.lerp(target, catch_up);
+
+    if position != transform.translation.truncate() {
+        transform.translation = position.extend(transform.translation.z);
     }
+}
+
+/// Keep the centre of the camera within the edges of the world.
+pub fn clamp_camera(config: Res<MapConfig>, mut camera: Query<&mut Transform, With<Camera>>) {
+    let mut transform = get_single_mut!(camera);
+
+    let position = transform.translation.truncate();
+    let clamped = config.world_shape.clamp(position, config.tile_size);
 
-    if projection.scale <= 3.0 && keyboard_input.pressed(KeyCode::Minus) {
-        projection.scale += 1.0 * time.delta_seconds();
+    if clamped != position {
+        transform.translation = clamped.extend(transform.translation.z);
     }
 }
@@ -6,25 +6,52 @@ use bevy_egui::egui::Checkbox;
 use bevy_egui::{egui, EguiContexts};
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
+use crate::camera::{CameraFollow, CameraZoom, PixelPerfect};
+use crate::input::{Action, InputBindings};
 use crate::map::config::MapConfig;
+use crate::map::heightmap::OutsideHeightmap;
+use crate::map::world_shape::{Falloff, WorldShape};
 use crate::map::{ChunkBorderState, ChunkReloadEvent};
+use crate::minimap::{MinimapConfig, MinimapOrientation};
 
 pub fn debug_plugin(app: &mut App) {
     app.init_resource::<DebugState>()
-        .add_plugins(WorldInspectorPlugin::new())
-        .add_systems(PostUpdate, debug_menu);
+        .add_plugins(WorldInspectorPlugin::new().run_if(debug_shown))
+        .add_systems(Update, toggle_debug)
+        .add_systems(PostUpdate, (debug_menu, bindings_menu).run_if(debug_shown));
 }
 
+/// Radius in tiles of an island when first switching to one.
+const DEFAULT_ISLAND_RADIUS: u32 = 500;
+/// Size in chunks of a wrapped world when first switching to one.
+const DEFAULT_WRAPPED_SIZE: u32 = 20;
+
 #[derive(Resource, Default)]
 struct DebugState {
     seed_text: String,
+    hidden:    bool,
+    /// The action waiting for a key to be pressed to bind to it.
+    rebinding: Option<Action>,
+}
+
+fn debug_shown(debug_state: Res<DebugState>) -> bool { !debug_state.hidden }
+
+fn toggle_debug(actions: Res<ButtonInput<Action>>, mut debug_state: ResMut<DebugState>) {
+    if actions.just_pressed(Action::ToggleDebug) {
+        debug_state.hidden = !debug_state.hidden;
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn debug_menu(
     mut contexts: EguiContexts,
     mut ev_chunk_reload: EventWriter<ChunkReloadEvent>,
     mut map_config: ResMut<MapConfig>,
     mut debug_state: ResMut<DebugState>,
+    mut minimap_config: ResMut<MinimapConfig>,
+    mut camera_follow: ResMut<CameraFollow>,
+    mut camera_zoom: ResMut<CameraZoom>,
+    mut pixel_perfect: ResMut<PixelPerfect>,
     mut next_chunk_borders_state: ResMut<NextState<ChunkBorderState>>,
     window: Query<&Window, With<PrimaryWindow>>,
     chunk_borders_state: Res<State<ChunkBorderState>>,
@@ -44,7 +71,7 @@ fn debug_menu(
             let seed = u64::from_str_radix(hex_string, 16).expect("Failed to parse seed");
             map_config.seed = seed;
             ev_chunk_reload.send(ChunkReloadEvent);
-        };
+        }
 
         ui.horizontal(|ui| {
             if ui.button("Random Seed").clicked() {
@@ -62,14 +89,46 @@ fn debug_menu(
             .changed()
         {
             ev_chunk_reload.send(ChunkReloadEvent);
-        };
+        }
 
         if ui
             .add(egui::Slider::new(&mut map_config.chunk_tile_count, 5..=100).text("Chunk Size"))
             .changed()
         {
             ev_chunk_reload.send(ChunkReloadEvent);
-        };
+        }
+
+        if ui
+            .add(Checkbox::new(
+                &mut map_config.pixel_per_tile,
+                "Pixel Per Tile Textures",
+            ))
+            .changed()
+        {
+            ev_chunk_reload.send(ChunkReloadEvent);
+        }
+
+        if world_shape_ui(ui, &mut map_config.world_shape) {
+            ev_chunk_reload.send(ChunkReloadEvent);
+        }
+
+        if let Some(terrain) = &mut map_config.heightmap {
+            let mut procedural = terrain.outside == OutsideHeightmap::Procedural;
+            if ui
+                .add(Checkbox::new(
+                    &mut procedural,
+                    "Procedural Outside Heightmap",
+                ))
+                .changed()
+            {
+                terrain.outside = if procedural {
+                    OutsideHeightmap::Procedural
+                } else {
+                    OutsideHeightmap::Ocean
+                };
+                ev_chunk_reload.send(ChunkReloadEvent);
+            }
+        }
 
         let mut chunk_borders = (*chunk_borders_state.get()).into();
         if ui
@@ -79,5 +138,168 @@ fn debug_menu(
             next_chunk_borders_state.set(chunk_borders_state.next());
             ev_chunk_reload.send(ChunkReloadEvent);
         }
+
+        ui.add(egui::Slider::new(&mut minimap_config.zoom, 0.25..=8.0).text("Minimap Zoom"));
+
+        let mut rotate_minimap = minimap_config.orientation == MinimapOrientation::RotateWithPlayer;
+        if ui
+            .add(Checkbox::new(&mut rotate_minimap, "Rotate Minimap"))
+            .changed()
+        {
+            minimap_config.orientation = if rotate_minimap {
+                MinimapOrientation::RotateWithPlayer
+            } else {
+                MinimapOrientation::FixedNorth
+            };
+        }
+
+        ui.add(Checkbox::new(&mut pixel_perfect.0, "Pixel Perfect Camera"));
+        camera_ui(ui, &mut camera_follow, &mut camera_zoom);
+    });
+}
+
+/// Controls for how the camera follows the player and zooms.
+fn camera_ui(ui: &mut egui::Ui, camera_follow: &mut CameraFollow, camera_zoom: &mut CameraZoom) {
+    ui.add(egui::Slider::new(&mut camera_follow.smoothing, 0.0..=20.0).text("Camera Smoothing"));
+    ui.add(egui::Slider::new(&mut camera_follow.lookahead, 0.0..=400.0).text("Camera Lookahead"));
+    ui.add(egui::Slider::new(&mut camera_follow.deadzone.x, 0.0..=400.0).text("Deadzone Width"));
+    ui.add(egui::Slider::new(&mut camera_follow.deadzone.y, 0.0..=400.0).text("Deadzone Height"));
+    ui.add(egui::Slider::new(&mut camera_zoom.easing, 0.0..=30.0).text("Zoom Easing"));
+    ui.add(
+        egui::Slider::new(&mut camera_zoom.min, 0.05..=camera_zoom.max)
+            .logarithmic(true)
+            .text("Min Zoom"),
+    );
+    ui.add(
+        egui::Slider::new(&mut camera_zoom.max, camera_zoom.min..=64.0)
+            .logarithmic(true)
+            .text("Max Zoom"),
+    );
+}
+
+/// Lists the keys and gamepad buttons bound to each [`Action`],
+/// click one to unbind it or `+` to bind the next key or button pressed.
+fn bindings_menu(
+    mut contexts: EguiContexts,
+    mut bindings: ResMut<InputBindings>,
+    mut debug_state: ResMut<DebugState>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_input: Res<ButtonInput<GamepadButton>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if window.is_empty() {
+        return;
+    }
+
+    if let Some(action) = debug_state.rebinding {
+        if let Some(key) = keyboard_input.get_just_pressed().next() {
+            bindings.bind(action, *key);
+            debug_state.rebinding = None;
+        } else if let Some(button) = gamepad_input.get_just_pressed().next() {
+            bindings.bind_button(action, button.button_type);
+            debug_state.rebinding = None;
+        }
+    }
+
+    egui::Window::new("Controls")
+        .default_open(false)
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Grid::new("bindings").show(ui, |ui| {
+                for action in Action::ALL {
+                    ui.label(action.label());
+
+                    ui.horizontal(|ui| {
+                        for key in bindings.keys(action).to_vec() {
+                            if ui.button(format!("{key:?}")).clicked() {
+                                bindings.unbind(action, key);
+                            }
+                        }
+                        for button in bindings.buttons(action).to_vec() {
+                            if ui.button(format!("Gamepad {button:?}")).clicked() {
+                                bindings.unbind_button(action, button);
+                            }
+                        }
+
+                        let text = if debug_state.rebinding == Some(action) {
+                            "Press a key or button..."
+                        } else {
+                            "+"
+                        };
+                        if ui.button(text).clicked() {
+                            debug_state.rebinding = Some(action);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+            if ui.button("Reset to Defaults").clicked() {
+                *bindings = InputBindings::default();
+            }
+        });
+}
+
+/// Controls for the [`WorldShape`], returning whether it changed.
+fn world_shape_ui(ui: &mut egui::Ui, world_shape: &mut WorldShape) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        let shapes = [
+            ("Infinite", WorldShape::Infinite),
+            (
+                "Island",
+                WorldShape::Island {
+                    radius:  DEFAULT_ISLAND_RADIUS,
+                    falloff: Falloff::default(),
+                },
+            ),
+            (
+                "Wrapped",
+                WorldShape::Wrapped {
+                    width:  DEFAULT_WRAPPED_SIZE,
+                    height: DEFAULT_WRAPPED_SIZE,
+                },
+            ),
+        ];
+
+        for (name, shape) in shapes {
+            let selected = std::mem::discriminant(world_shape) == std::mem::discriminant(&shape);
+            if ui.radio(selected, name).clicked() && !selected {
+                *world_shape = shape;
+                changed = true;
+            }
+        }
     });
+
+    match world_shape {
+        WorldShape::Infinite => {}
+        WorldShape::Island { radius, falloff } => {
+            changed |= ui
+                .add(egui::Slider::new(radius, 50..=5000).text("Island Radius"))
+                .changed();
+
+            let mut square = *falloff == Falloff::Square;
+            if ui
+                .add(Checkbox::new(&mut square, "Square Island"))
+                .changed()
+            {
+                *falloff = if square {
+                    Falloff::Square
+                } else {
+                    Falloff::Radial
+                };
+                changed = true;
+            }
+        }
+        WorldShape::Wrapped { width, height } => {
+            changed |= ui
+                .add(egui::Slider::new(width, 2..=200).text("Wrapped Width"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(height, 2..=200).text("Wrapped Height"))
+                .changed();
+        }
+    }
+
+    changed
 }
@@ -0,0 +1,404 @@
+//! Input actions, so systems don't need to know which keys or gamepad buttons they are bound to.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use bevy::app::AppExit;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy_egui::EguiContext;
+use serde::{Deserialize, Serialize};
+
+pub fn input_plugin(app: &mut App) {
+    app.init_resource::<ButtonInput<Action>>()
+        .init_resource::<MovementInput>()
+        .init_resource::<UiFocus>()
+        .insert_resource(load_bindings())
+        .add_systems(
+            PreUpdate,
+            (update_ui_focus, update_actions).chain().after(InputSystem),
+        )
+        .add_systems(Update, quit)
+        .add_systems(Last, save_bindings);
+}
+
+/// Where the players key bindings are saved.
+pub const BINDINGS_PATH: &str = "saves/bindings.ron";
+/// How far a gamepad stick has to be tilted before the player moves, as a fraction of a full tilt.
+pub const STICK_DEADZONE: f32 = 0.2;
+
+/// Something the player can do, read from [`ButtonInput<Action>`] rather than the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    ZoomIn,
+    ZoomOut,
+    ToggleWorldMap,
+    ToggleDebug,
+    ToggleFreeCamera,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Self; 10] = [
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::ZoomIn,
+        Self::ZoomOut,
+        Self::ToggleWorldMap,
+        Self::ToggleDebug,
+        Self::ToggleFreeCamera,
+        Self::Quit,
+    ];
+
+    /// Name of the action for menus.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "Move Left",
+            Self::MoveRight => "Move Right",
+            Self::MoveUp => "Move Up",
+            Self::MoveDown => "Move Down",
+            Self::ZoomIn => "Zoom In",
+            Self::ZoomOut => "Zoom Out",
+            Self::ToggleWorldMap => "World Map",
+            Self::ToggleDebug => "Debug Menu",
+            Self::ToggleFreeCamera => "Free Camera",
+            Self::Quit => "Quit",
+        }
+    }
+
+    /// The keys the action is bound to until the player changes them.
+    const fn default_keys(self) -> &'static [KeyCode] {
+        match self {
+            Self::MoveLeft => &[KeyCode::ArrowLeft, KeyCode::KeyA],
+            Self::MoveRight => &[KeyCode::ArrowRight, KeyCode::KeyD],
+            Self::MoveUp => &[KeyCode::ArrowUp, KeyCode::KeyW],
+            Self::MoveDown => &[KeyCode::ArrowDown, KeyCode::KeyS],
+            Self::ZoomIn => &[KeyCode::Equal],
+            Self::ZoomOut => &[KeyCode::Minus],
+            Self::ToggleWorldMap => &[KeyCode::KeyM],
+            Self::ToggleDebug => &[KeyCode::F3],
+            Self::ToggleFreeCamera => &[KeyCode::KeyC],
+            Self::Quit => &[KeyCode::Escape],
+        }
+    }
+
+    /// The gamepad buttons the action is bound to until the player changes them.
+    const fn default_buttons(self) -> &'static [GamepadButtonType] {
+        match self {
+            Self::MoveLeft => &[GamepadButtonType::DPadLeft],
+            Self::MoveRight => &[GamepadButtonType::DPadRight],
+            Self::MoveUp => &[GamepadButtonType::DPadUp],
+            Self::MoveDown => &[GamepadButtonType::DPadDown],
+            Self::ZoomIn => &[
+                GamepadButtonType::RightTrigger,
+                GamepadButtonType::RightTrigger2,
+            ],
+            Self::ZoomOut => &[
+                GamepadButtonType::LeftTrigger,
+                GamepadButtonType::LeftTrigger2,
+            ],
+            Self::ToggleWorldMap => &[GamepadButtonType::Select],
+            Self::ToggleDebug => &[GamepadButtonType::Start],
+            Self::ToggleFreeCamera | Self::Quit => &[],
+        }
+    }
+
+    /// Direction the action moves the player in, if it is a movement action.
+    const fn direction(self) -> Option<Vec2> {
+        match self {
+            Self::MoveLeft => Some(Vec2::NEG_X),
+            Self::MoveRight => Some(Vec2::X),
+            Self::MoveUp => Some(Vec2::Y),
+            Self::MoveDown => Some(Vec2::NEG_Y),
+            _ => None,
+        }
+    }
+}
+
+/// Whether egui is using the keyboard or mouse, such as when typing into a text field or over a window,
+/// in which case the game should ignore them.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UiFocus {
+    pub keyboard: bool,
+    pub pointer:  bool,
+}
+
+/// Which way and how fast the player wants to move, from the movement actions and gamepad sticks.
+///
+/// At most one unit long, shorter when a stick is only partly tilted.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct MovementInput(pub Vec2);
+
+/// The keys and gamepad buttons each [`Action`] is bound to.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "BindingsData", into = "BindingsData")]
+pub struct InputBindings {
+    keys:    BTreeMap<Action, Vec<KeyCode>>,
+    buttons: BTreeMap<Action, Vec<GamepadButtonType>>,
+}
+
+/// Bindings as written in the bindings file.
+#[derive(Serialize, Deserialize)]
+struct BindingsData {
+    #[serde(default)]
+    keys:    BTreeMap<Action, Vec<KeyCode>>,
+    #[serde(default)]
+    buttons: BTreeMap<Action, Vec<GamepadButtonType>>,
+}
+
+impl InputBindings {
+    /// Loads bindings from a file.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ron::from_str(&data).map_err(|e| e.to_string())
+    }
+
+    /// Writes the bindings to a file, creating its directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be written.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let data = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(path, data).map_err(|e| e.to_string())
+    }
+
+    /// The keys an action is bound to.
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Binds a key to an action, unbinding it from any other action.
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        for keys in self.keys.values_mut() {
+            keys.retain(|bound| *bound != key);
+        }
+        self.keys.entry(action).or_default().push(key);
+    }
+
+    /// Unbinds a key from an action.
+    pub fn unbind(&mut self, action: Action, key: KeyCode) {
+        if let Some(keys) = self.keys.get_mut(&action) {
+            keys.retain(|bound| *bound != key);
+        }
+    }
+
+    /// The gamepad buttons an action is bound to.
+    pub fn buttons(&self, action: Action) -> &[GamepadButtonType] {
+        self.buttons.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Binds a gamepad button to an action, unbinding it from any other action.
+    pub fn bind_button(&mut self, action: Action, button: GamepadButtonType) {
+        for buttons in self.buttons.values_mut() {
+            buttons.retain(|bound| *bound != button);
+        }
+        self.buttons.entry(action).or_default().push(button);
+    }
+
+    /// Unbinds a gamepad button from an action.
+    pub fn unbind_button(&mut self, action: Action, button: GamepadButtonType) {
+        if let Some(buttons) = self.buttons.get_mut(&action) {
+            buttons.retain(|bound| *bound != button);
+        }
+    }
+
+    /// Whether any key bound to the action is pressed, or any button bound to it on a connected gamepad.
+    pub fn pressed(
+        &self,
+        action: Action,
+        keyboard_input: &ButtonInput<KeyCode>,
+        gamepads: &Gamepads,
+        gamepad_input: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        keyboard_input.any_pressed(self.keys(action).iter().copied())
+            || gamepad_input.any_pressed(self.gamepad_buttons(action, gamepads))
+    }
+
+    /// Whether any key or connected gamepad button bound to the action was pressed this frame.
+    pub fn just_pressed(
+        &self,
+        action: Action,
+        keyboard_input: &ButtonInput<KeyCode>,
+        gamepads: &Gamepads,
+        gamepad_input: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        keyboard_input.any_just_pressed(self.keys(action).iter().copied())
+            || gamepad_input.any_just_pressed(self.gamepad_buttons(action, gamepads))
+    }
+
+    /// The buttons bound to the action on every connected gamepad.
+    fn gamepad_buttons<'a>(
+        &'a self,
+        action: Action,
+        gamepads: &'a Gamepads,
+    ) -> impl Iterator<Item = GamepadButton> + 'a {
+        gamepads.iter().flat_map(move |gamepad| {
+            self.buttons(action)
+                .iter()
+                .map(move |&button| GamepadButton::new(gamepad, button))
+        })
+    }
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            keys:    Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_keys().to_vec()))
+                .collect(),
+            buttons: Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_buttons().to_vec()))
+                .collect(),
+        }
+    }
+}
+
+/// Actions missing from a bindings file, such as ones added since it was saved, keep their defaults.
+impl From<BindingsData> for InputBindings {
+    fn from(data: BindingsData) -> Self {
+        let mut bindings = Self::default();
+        bindings.keys.extend(data.keys);
+        bindings.buttons.extend(data.buttons);
+        bindings
+    }
+}
+
+impl From<InputBindings> for BindingsData {
+    fn from(bindings: InputBindings) -> Self {
+        Self {
+            keys:    bindings.keys,
+            buttons: bindings.buttons,
+        }
+    }
+}
+
+fn load_bindings() -> InputBindings {
+    let path = Path::new(BINDINGS_PATH);
+    if !path.exists() {
+        return InputBindings::default();
+    }
+
+    InputBindings::load(path).unwrap_or_else(|e| {
+        error!("Failed to load key bindings from {BINDINGS_PATH}: {e}");
+        InputBindings::default()
+    })
+}
+
+/// Save the bindings whenever the player changes them.
+fn save_bindings(bindings: Res<InputBindings>) {
+    if !bindings.is_changed() || bindings.is_added() {
+        return;
+    }
+
+    match bindings.save(Path::new(BINDINGS_PATH)) {
+        Ok(()) => debug!("Saved key bindings to {BINDINGS_PATH}"),
+        Err(e) => error!("Failed to save key bindings to {BINDINGS_PATH}: {e}"),
+    }
+}
+
+/// Check whether any egui context wants the keyboard or mouse.
+fn update_ui_focus(mut contexts: Query<&mut EguiContext>, mut focus: ResMut<UiFocus>) {
+    let mut new_focus = UiFocus::default();
+
+    for mut context in &mut contexts {
+        let ctx = context.get_mut();
+        new_focus.keyboard |= ctx.wants_keyboard_input();
+        new_focus.pointer |= ctx.wants_pointer_input();
+    }
+
+    focus.set_if_neq(new_focus);
+}
+
+/// Press and release actions to match the keys and buttons bound to them,
+/// and work out the [`MovementInput`] from them and any gamepad sticks.
+///
+/// Every action is released while egui has the keyboard. Keys still held when it lets go
+/// have to be pressed again, so pressing Esc to leave a text field doesn't also quit.
+#[allow(clippy::too_many_arguments)]
+pub fn update_actions(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_input: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    bindings: Res<InputBindings>,
+    focus: Res<UiFocus>,
+    mut actions: ResMut<ButtonInput<Action>>,
+    mut movement: ResMut<MovementInput>,
+) {
+    actions.clear();
+
+    if focus.keyboard {
+        actions.release_all();
+        movement.0 = Vec2::ZERO;
+        return;
+    }
+
+    for action in Action::ALL {
+        let pressed = bindings.pressed(action, &keyboard_input, &gamepads, &gamepad_input);
+        let just_pressed =
+            bindings.just_pressed(action, &keyboard_input, &gamepads, &gamepad_input);
+
+        if pressed && (just_pressed || actions.pressed(action)) {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+
+    let direction: Vec2 = actions
+        .get_pressed()
+        .filter_map(|action| action.direction())
+        .sum();
+    let tilt: Vec2 = gamepads
+        .iter()
+        .map(|gamepad| stick_tilt(gamepad, &gamepad_axes))
+        .sum();
+
+    movement.0 = (direction.normalize_or_zero() + tilt).clamp_length_max(1.0);
+}
+
+/// Quit when [`Action::Quit`] is pressed, Esc unless rebound.
+fn quit(actions: Res<ButtonInput<Action>>, mut ev_exit: EventWriter<AppExit>) {
+    if actions.just_pressed(Action::Quit) {
+        ev_exit.send(AppExit);
+    }
+}
+
+/// How far the left stick of a gamepad is tilted,
+/// zero within the [`STICK_DEADZONE`] and growing to one at a full tilt.
+fn stick_tilt(gamepad: Gamepad, axes: &Axis<GamepadAxis>) -> Vec2 {
+    let stick_axis = |axis_type| {
+        axes.get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or(0.0)
+    };
+    let tilt = Vec2::new(
+        stick_axis(GamepadAxisType::LeftStickX),
+        stick_axis(GamepadAxisType::LeftStickY),
+    );
+
+    let length = tilt.length();
+    if length <= STICK_DEADZONE {
+        return Vec2::ZERO;
+    }
+
+    tilt / length * ((length - STICK_DEADZONE) / (1.0 - STICK_DEADZONE)).min(1.0)
+}
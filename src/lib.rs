@@ -0,0 +1,19 @@
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_precision_loss)]
+#![allow(clippy::cast_sign_loss)]
+#![allow(clippy::cast_possible_wrap)]
+#![allow(clippy::needless_pass_by_value)]
+#![allow(clippy::module_name_repetitions)]
+#![allow(clippy::must_use_candidate)]
+#![allow(clippy::return_self_not_must_use)]
+
+pub mod animation;
+pub mod camera;
+pub mod debug;
+pub mod input;
+pub mod map;
+pub mod minimap;
+pub mod player;
+pub mod save;
+mod util;
+pub mod world_map;
@@ -1,22 +1,14 @@
-#![allow(clippy::cast_possible_truncation)]
-#![allow(clippy::cast_precision_loss)]
-#![allow(clippy::cast_sign_loss)]
-#![allow(clippy::cast_possible_wrap)]
-#![allow(clippy::needless_pass_by_value)]
-#![allow(clippy::module_name_repetitions)]
-
-mod camera;
-mod debug;
-mod map;
-mod player;
-mod util;
-
 use bevy::log::LogPlugin;
 use bevy::prelude::*;
-use camera::camera_plugin;
-use debug::debug_plugin;
-use map::map_plugin;
-use player::player_plugin;
+use bevy_game::animation::animation_plugin;
+use bevy_game::camera::camera_plugin;
+use bevy_game::debug::debug_plugin;
+use bevy_game::input::input_plugin;
+use bevy_game::map::map_plugin;
+use bevy_game::minimap::minimap_plugin;
+use bevy_game::player::player_plugin;
+use bevy_game::save::save_plugin;
+use bevy_game::world_map::world_map_plugin;
 
 fn main() {
     App::new()
@@ -28,11 +20,15 @@ fn main() {
                     update_subscriber: None,
                 })
                 .set(ImagePlugin::default_nearest()),
+            input_plugin,
             map_plugin,
             player_plugin,
+            animation_plugin,
             camera_plugin,
             debug_plugin,
+            minimap_plugin,
+            world_map_plugin,
+            save_plugin,
         ))
-        .add_systems(Update, bevy::window::close_on_esc)
         .run();
 }
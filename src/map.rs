@@ -1,41 +1,60 @@
 //! Map generation and rendering.
 
-mod chunk;
+pub mod chunk;
 pub mod chunk_position;
 pub mod config;
-mod tile_kind;
-
+pub mod heightmap;
+pub mod noise;
+pub mod region;
+pub mod stamp;
+pub mod tile_registry;
+pub mod tiled;
+pub mod world_shape;
+
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Instant;
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::utils::{HashMap, HashSet};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use worldgen::noise::perlin::PerlinNoise;
-use worldgen::noisemap::{self, NoiseMapGenerator, Seed, Step};
-use worldgen::world::Size;
 
 use self::chunk::Chunk;
 use self::chunk_position::ChunkPosition;
 use self::config::MapConfig;
+use self::heightmap::{Heightmap, HeightmapTerrain, HEIGHTMAP_PATH};
+use self::noise::NoiseMap;
+use self::stamp::{load_stamps, Stamp, STAMPS_PATH};
+use self::tile_registry::{TileDef, TileRegistry, TILES_PATH};
+use self::world_shape::WorldShape;
+use crate::camera::clamp_camera;
 use crate::get_single;
 use crate::player::sprite_movement;
 
 pub fn map_plugin(app: &mut App) {
     app.add_event::<ChunkReloadEvent>()
         .init_resource::<Map>()
+        .insert_resource(load_tile_registry())
         .init_resource::<MapConfig>()
         .init_resource::<NoiseMap>()
         .init_resource::<UnspawnedChunks>()
+        .init_resource::<ChunkLod>()
+        .init_resource::<ExploredChunks>()
         .init_state::<ChunkBorderState>()
+        .add_systems(Startup, (load_heightmap, load_world_stamps))
         .add_systems(
             Update,
             (
-                (update_noisemap, chunk_unload).run_if(on_event::<ChunkReloadEvent>()),
+                update_lod,
+                (update_noisemap, reset_explored, chunk_unload)
+                    .run_if(on_event::<ChunkReloadEvent>()),
                 calculate_chunks,
                 spawn_chunks,
             )
                 .chain()
-                .after(sprite_movement),
+                .after(sprite_movement)
+                .after(clamp_camera),
         );
 }
 
@@ -67,45 +86,197 @@ impl From<ChunkBorderState> for bool {
 #[derive(Event)]
 pub struct ChunkReloadEvent;
 
-#[derive(Resource, Deref, DerefMut)]
-struct NoiseMap(noisemap::NoiseMap<PerlinNoise>);
+/// The highest [`ChunkLod`] level, where chunk textures are reduced to a single pixel per tile
+/// for the default tile size.
+const MAX_LOD: u32 = 4;
+
+/// The level of detail chunk textures are generated at, picked from the [`Camera`] zoom.
+///
+/// Every level halves the amount of pixels a tile is drawn with, down to a single pixel.
+#[derive(Resource, Default, Deref, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkLod(pub u32);
+
+impl ChunkLod {
+    /// Picks the level of detail for an [`OrthographicProjection::scale`].
+    pub fn from_scale(scale: f32) -> Self {
+        if scale <= 1.0 {
+            return Self(0);
+        }
+
+        Self((scale.log2().floor() as u32).min(MAX_LOD))
+    }
+
+    /// Size of a tile in pixels within a chunk texture at this level of detail.
+    pub fn tile_pixels(self, config: &MapConfig) -> u32 {
+        if config.pixel_per_tile {
+            return 1;
+        }
+
+        (config.tile_size as u32 >> self.0).max(1)
+    }
+}
+
+/// The currently loaded chunks and the entities they are spawned as.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Map(HashMap<ChunkPosition, Entity>);
+
+/// Every chunk that has been spawned in the current world.
+#[derive(Resource, Debug)]
+pub struct ExploredChunks {
+    /// The seed of the world the chunks were explored in.
+    pub seed:             u64,
+    /// The chunk size the chunk positions were recorded with.
+    pub chunk_tile_count: usize,
+    /// The shape of the world the chunks were explored in.
+    pub world_shape:      WorldShape,
+    /// The heightmap the explored terrain was generated from.
+    pub heightmap:        Option<HeightmapTerrain>,
+    /// The stamps placed over the explored terrain.
+    pub stamps:           Vec<Stamp>,
+    pub chunks:           HashSet<ChunkPosition>,
+}
+
+impl ExploredChunks {
+    pub fn new(config: &MapConfig) -> Self {
+        Self {
+            seed:             config.seed,
+            chunk_tile_count: config.chunk_tile_count,
+            world_shape:      config.world_shape,
+            heightmap:        config.heightmap.clone(),
+            stamps:           config.stamps.clone(),
+            chunks:           HashSet::new(),
+        }
+    }
 
-impl FromWorld for NoiseMap {
+    /// Whether the explored chunks belong to the world generated by `config`.
+    pub fn matches(&self, config: &MapConfig) -> bool {
+        self.seed == config.seed
+            && self.chunk_tile_count == config.chunk_tile_count
+            && self.world_shape == config.world_shape
+            && self.heightmap == config.heightmap
+            && self.stamps == config.stamps
+    }
+}
+
+impl FromWorld for ExploredChunks {
     fn from_world(world: &mut World) -> Self {
         let config = world
             .get_resource::<MapConfig>()
-            .expect("NoiseMap must be initialised before MapConfig");
-
-        let noise = PerlinNoise::new();
+            .expect("MapConfig must be initialised before ExploredChunks");
 
-        Self(
-            noisemap::NoiseMap::new(noise)
-                .set(Seed::of(config.seed))
-                .set(Size::of(
-                    config.chunk_tile_count as i64,
-                    config.chunk_tile_count as i64,
-                ))
-                .set(Step::of(0.01, 0.01)),
-        )
+        Self::new(config)
     }
 }
 
-#[derive(Resource, Default, Deref, DerefMut)]
-struct Map(HashMap<ChunkPosition, Entity>);
+/// Reads the tiles in currently loaded chunks.
+#[derive(SystemParam)]
+pub struct LoadedTiles<'w, 's> {
+    map:      Res<'w, Map>,
+    config:   Res<'w, MapConfig>,
+    registry: Res<'w, TileRegistry>,
+    chunks:   Query<'w, 's, &'static Chunk>,
+}
+
+impl LoadedTiles<'_, '_> {
+    /// Gets the tile at world tile coordinates, if the chunk containing it is loaded.
+    pub fn get(&self, tile: IVec2) -> Option<&TileDef> {
+        let (position, local) = ChunkPosition::from_tile(tile, &self.config);
+        let chunk = self.chunks.get(*self.map.get(&position)?).ok()?;
+
+        self.registry.get(chunk.get(local)?)
+    }
+
+    /// Whether any chunks have been loaded or unloaded since the last time the system ran.
+    pub fn is_changed(&self) -> bool { self.map.is_changed() }
+}
 
 /// A list of unspawned [`Chunks`](Chunk) that have been generated by
 /// [`calculate_chunks`] that will be then generated and spawned by [`spawn_chunks`].
 #[derive(Resource, Default, Deref, DerefMut)]
 struct UnspawnedChunks(Vec<ChunkPosition>);
 
+/// Loads the tiles from [`TILES_PATH`], falling back to the built in tiles if that fails.
+fn load_tile_registry() -> TileRegistry {
+    TileRegistry::load(Path::new(TILES_PATH)).unwrap_or_else(|e| {
+        error!("Failed to load tiles from {TILES_PATH}, using the default tiles: {e}");
+        TileRegistry::default()
+    })
+}
+
+/// Use the heightmap at [`HEIGHTMAP_PATH`] for the terrain if there is one.
+pub fn load_heightmap(
+    mut config: ResMut<MapConfig>,
+    mut ev_chunk_reload: EventWriter<ChunkReloadEvent>,
+) {
+    let path = Path::new(HEIGHTMAP_PATH);
+    if !path.exists() {
+        return;
+    }
+
+    match Heightmap::load(path) {
+        Ok(heightmap) => {
+            info!(
+                "Loaded {}x{} heightmap from {HEIGHTMAP_PATH}",
+                heightmap.size().x,
+                heightmap.size().y
+            );
+            config.heightmap = Some(HeightmapTerrain {
+                heightmap: Arc::new(heightmap),
+                outside:   default(),
+            });
+            ev_chunk_reload.send(ChunkReloadEvent);
+        }
+        Err(e) => error!("Failed to load heightmap from {HEIGHTMAP_PATH}: {e}"),
+    }
+}
+
+/// Place the stamps from [`STAMPS_PATH`] into the world if there are any.
+pub fn load_world_stamps(
+    mut config: ResMut<MapConfig>,
+    mut ev_chunk_reload: EventWriter<ChunkReloadEvent>,
+    registry: Res<TileRegistry>,
+) {
+    let path = Path::new(STAMPS_PATH);
+    if !path.exists() {
+        return;
+    }
+
+    match load_stamps(path, &registry) {
+        Ok(stamps) if stamps.is_empty() => {}
+        Ok(stamps) => {
+            info!("Loaded {} stamps from {STAMPS_PATH}", stamps.len());
+            config.stamps = stamps;
+            ev_chunk_reload.send(ChunkReloadEvent);
+        }
+        Err(e) => error!("Failed to load stamps from {STAMPS_PATH}: {e}"),
+    }
+}
+
 /// Update the [`NoiseMap`] if its config has changed.
 fn update_noisemap(mut noisemap: ResMut<NoiseMap>, config: Res<MapConfig>) {
-    noisemap.0 = noisemap
-        .set(Size::of(
-            config.chunk_tile_count as i64,
-            config.chunk_tile_count as i64,
-        ))
-        .set(Seed::of(config.seed));
+    *noisemap = NoiseMap::new(&config);
+}
+
+/// Forget the [`ExploredChunks`] if the terrain or chunk size has changed, as they no longer line up with the world.
+fn reset_explored(mut explored: ResMut<ExploredChunks>, config: Res<MapConfig>) {
+    if !explored.matches(&config) {
+        debug!("Resetting explored chunks");
+        *explored = ExploredChunks::new(&config);
+    }
+}
+
+/// Update the [`ChunkLod`] from the [`Camera`] zoom, reloading all chunks when it changes.
+fn update_lod(
+    mut lod: ResMut<ChunkLod>,
+    mut ev_chunk_reload: EventWriter<ChunkReloadEvent>,
+    camera_projection: Query<&OrthographicProjection, With<Camera>>,
+) {
+    let camera_projection = get_single!(camera_projection);
+
+    if lod.set_if_neq(ChunkLod::from_scale(camera_projection.scale)) {
+        debug!("Chunk level of detail changed to {}", **lod);
+        ev_chunk_reload.send(ChunkReloadEvent);
+    }
 }
 
 /// If a [`ChunkReloadEvent`] is created, all chunks get unloaded & despawned to then be reloaded.
@@ -190,14 +361,18 @@ fn calculate_chunks(
 }
 
 /// Takes the chunk positions that were calculated in [`calculate_chunks()`] and generates and spawns them.
+#[allow(clippy::too_many_arguments)]
 fn spawn_chunks(
     mut commands: Commands,
     mut unspawned_chunks: ResMut<UnspawnedChunks>,
     mut map: ResMut<Map>,
     mut assets: ResMut<Assets<Image>>,
+    mut explored: ResMut<ExploredChunks>,
     config: Res<MapConfig>,
     noisemap: Res<NoiseMap>,
+    registry: Res<TileRegistry>,
     chunk_borders: Res<State<ChunkBorderState>>,
+    lod: Res<ChunkLod>,
 ) {
     if unspawned_chunks.is_empty() {
         return;
@@ -208,8 +383,9 @@ fn spawn_chunks(
     let chunk_package: Vec<(ChunkPosition, Chunk, Image)> = unspawned_chunks
         .par_iter()
         .map(|position| {
-            let chunk = Chunk::new(*position, &noisemap, &config);
-            let texture = chunk.generate_texture_image(&config, *chunk_borders.get());
+            let chunk = Chunk::new(*position, &noisemap, &config, &registry);
+            let texture =
+                chunk.generate_texture_image(&config, &registry, *chunk_borders.get(), *lod);
             (*position, chunk, texture)
         })
         .collect();
@@ -217,6 +393,7 @@ fn spawn_chunks(
     let count = chunk_package.len();
 
     for (position, chunk, texture) in chunk_package {
+        explored.chunks.insert(position.wrap(&config));
         let texture = assets.add(texture);
         chunk.render(&mut commands, &mut map, &config, texture, position);
     }
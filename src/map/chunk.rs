@@ -1,106 +1,229 @@
 use bevy::prelude::*;
 use bevy::render::render_asset::RenderAssetUsages;
-use image::{DynamicImage, Rgba};
-use imageproc::drawing::draw_filled_rect_mut;
-use imageproc::rect::Rect;
-use strum::IntoEnumIterator;
-use worldgen::noise::perlin::PerlinNoise;
-use worldgen::noisemap::{self, NoiseMapGeneratorBase};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
 
 use super::chunk_position::ChunkPosition;
 use super::config::MapConfig;
-use super::tile_kind::TileKind;
-use super::{ChunkBorderState, Map};
+use super::noise::NoiseMap;
+use super::tile_registry::{TileId, TileRegistry, MISSING_TILE_COLOR};
+use super::{ChunkBorderState, ChunkLod, Map};
 
-#[derive(Component, Deref, DerefMut)]
-pub struct Chunk(pub Vec<Vec<TileKind>>);
+/// Width of the chunk border in pixels at full resolution.
+const CHUNK_BORDER_WIDTH: usize = 5;
+/// Chunk textures are stored as RGBA.
+const BYTES_PER_PIXEL: usize = 4;
+
+/// A square of tiles, stored as a flat list of [`TileId`]s.
+///
+/// Tiles are indexed by local tile coordinates, row by row from the bottom left of the chunk.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ChunkData")]
+pub struct Chunk {
+    size:  usize,
+    tiles: Vec<u8>,
+}
+
+/// Unchecked [`Chunk`] as read from a save, validated before becoming a [`Chunk`].
+#[derive(Deserialize)]
+struct ChunkData {
+    size:  usize,
+    tiles: Vec<u8>,
+}
+
+impl TryFrom<ChunkData> for Chunk {
+    type Error = String;
+
+    fn try_from(data: ChunkData) -> Result<Self, Self::Error> {
+        if data.tiles.len() != data.size * data.size {
+            return Err(format!(
+                "chunk of size {} has {} tiles",
+                data.size,
+                data.tiles.len()
+            ));
+        }
+
+        Ok(Self {
+            size:  data.size,
+            tiles: data.tiles,
+        })
+    }
+}
 
 impl Chunk {
-    /// Generates a [`Chunk`] from the noisemap for a given position.
+    /// Generates a [`Chunk`] from the noisemap for a given position, with any stamps placed over it.
     pub fn new(
         position: ChunkPosition,
-        noisemap: &noisemap::NoiseMap<PerlinNoise>,
+        noisemap: &NoiseMap,
         config: &MapConfig,
+        registry: &TileRegistry,
     ) -> Self {
-        let chunk_noise = noisemap.generate_chunk(i64::from(position.x), i64::from(position.y));
+        let chunk_noise = noisemap.generate_chunk(position, config);
 
-        let mut tiles =
-            vec![vec![TileKind::Grass; config.chunk_tile_count]; config.chunk_tile_count];
+        let mut chunk = Self {
+            size:  config.chunk_tile_count,
+            tiles: chunk_noise
+                .iter()
+                .flatten()
+                .map(|height| registry.terrain(*height).0)
+                .collect(),
+        };
 
-        for (row_index, row) in chunk_noise.iter().enumerate() {
-            for (tile_index, tile) in row.iter().enumerate() {
-                let tile_type = tile.into();
+        let wrapped = position.wrap(config);
+        for stamp in &config.stamps {
+            stamp.apply(wrapped, &mut chunk, config);
+        }
 
-                tiles[row_index][tile_index] = tile_type;
-            }
+        chunk
+    }
+
+    /// A [`Chunk`] of `size` by `size` tiles all of the same kind.
+    pub fn filled(size: usize, tile: TileId) -> Self {
+        Self {
+            size,
+            tiles: vec![tile.0; size * size],
         }
+    }
+
+    /// The amount of tiles along each side of the chunk.
+    pub const fn size(&self) -> usize { self.size }
+
+    /// The [`TileId`] at local tile coordinates, if they are within the chunk.
+    pub fn get(&self, local: UVec2) -> Option<TileId> {
+        self.index(local).map(|index| TileId(self.tiles[index]))
+    }
 
-        Self(tiles)
+    /// Sets the [`TileId`] at local tile coordinates.
+    ///
+    /// # Panics
+    ///
+    /// If the coordinates are outside of the chunk.
+    pub fn set(&mut self, local: UVec2, tile: TileId) {
+        let index = self
+            .index(local)
+            .unwrap_or_else(|| panic!("{local} is outside of a chunk of size {}", self.size));
+        self.tiles[index] = tile.0;
+    }
+
+    /// Every tile in the chunk with its local tile coordinates, row by row from the bottom.
+    pub fn iter(&self) -> impl Iterator<Item = (UVec2, TileId)> + '_ {
+        self.tiles.iter().enumerate().map(|(index, &id)| {
+            let local = UVec2::new((index % self.size) as u32, (index / self.size) as u32);
+            (local, TileId(id))
+        })
+    }
+
+    fn id_rows(&self) -> std::slice::ChunksExact<'_, u8> {
+        self.tiles.chunks_exact(self.size.max(1))
+    }
+
+    fn index(&self, local: UVec2) -> Option<usize> {
+        let (x, y) = (local.x as usize, local.y as usize);
+        (x < self.size && y < self.size).then_some(y * self.size + x)
     }
 
     /// Convert a [`Chunk`] and its data into a bevy
     /// [`Image`](https://docs.rs/bevy/latest/bevy/render/texture/struct.Image.html) to be used for creating textures.
     ///
     /// Image is still required to be registered in [`Assets<Image>`] to be used as a texture.
+    /// The resolution of the image is reduced depending on `lod`.
     pub fn generate_texture_image(
         &self,
         config: &MapConfig,
+        registry: &TileRegistry,
         chunk_borders: ChunkBorderState,
+        lod: ChunkLod,
     ) -> Image {
-        let chunk_size = config.chunk_size() as u32;
-        let tile_size = config.tile_size as u32;
+        let tile_size = lod.tile_pixels(config) as usize;
+        let chunk_size = config.chunk_tile_count * tile_size;
+        let row_bytes = chunk_size * BYTES_PER_PIXEL;
+        let tile_bytes = tile_size * BYTES_PER_PIXEL;
+        let pixels = TilePixels::new(registry, tile_size);
 
-        let mut dyn_image = DynamicImage::new_rgb8(chunk_size, chunk_size);
+        let mut data = vec![0; row_bytes * chunk_size];
 
         // Fill image completely with one colour if all tiles are the same, otherwise draw the tiles.
-        if let Some(tile_type) = self.is_uniform_type() {
-            let color: Color = tile_type.into();
-            draw_filled_rect_mut(
-                &mut dyn_image,
-                Rect::at(0, 0).of_size(chunk_size, chunk_size),
-                Rgba(color.as_rgba_u8()),
-            );
+        if let Some(id) = self
+            .is_uniform_type()
+            .filter(|id| pixels.texture(*id).is_none())
+        {
+            fill(&mut data, pixels.color(id));
         } else {
-            for (row_index, row) in self.0.iter().rev().enumerate() {
-                let row_index = row_index as u32;
-
-                for (tile_index, tile_type) in row.iter().enumerate() {
-                    let tile_index = tile_index as u32;
-                    let color: Color = tile_type.into();
-
-                    draw_filled_rect_mut(
-                        &mut dyn_image,
-                        Rect::at(
-                            (tile_index * tile_size) as i32,
-                            (row_index * tile_size) as i32,
-                        )
-                        .of_size(tile_size, tile_size),
-                        Rgba(color.as_rgba_u8()),
-                    );
+            // Image rows go top to bottom, so the tile rows are drawn in reverse.
+            for (row, image_rows) in self
+                .id_rows()
+                .rev()
+                .zip(data.chunks_exact_mut(row_bytes * tile_size))
+            {
+                if row.iter().any(|&id| pixels.texture(TileId(id)).is_some()) {
+                    // Textures differ on every row of pixels, so each one is drawn.
+                    for (y, image_row) in image_rows.chunks_exact_mut(row_bytes).enumerate() {
+                        for (&id, tile_pixels) in
+                            row.iter().zip(image_row.chunks_exact_mut(tile_bytes))
+                        {
+                            match pixels.texture(TileId(id)) {
+                                Some(texture) => tile_pixels.copy_from_slice(
+                                    &texture[y * tile_bytes..(y + 1) * tile_bytes],
+                                ),
+                                None => fill(tile_pixels, pixels.color(TileId(id))),
+                            }
+                        }
+                    }
+                    continue;
+                }
+
+                let (first_row, other_rows) = image_rows.split_at_mut(row_bytes);
+
+                // Draw a single row of pixels for the tiles, then copy it for the rest of the tiles height.
+                for (&id, tile_pixels) in row.iter().zip(first_row.chunks_exact_mut(tile_bytes)) {
+                    fill(tile_pixels, pixels.color(TileId(id)));
+                }
+
+                for image_row in other_rows.chunks_exact_mut(row_bytes) {
+                    image_row.copy_from_slice(first_row);
                 }
             }
         }
 
         if chunk_borders == ChunkBorderState::Shown {
-            Self::draw_chunk_border(&mut dyn_image, config);
+            Self::draw_chunk_border(&mut data, chunk_size, tile_size, config);
         }
 
-        Image::from_dynamic(dyn_image, true, RenderAssetUsages::RENDER_WORLD)
+        Image::new(
+            Extent3d {
+                width:                 chunk_size as u32,
+                height:                chunk_size as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        )
     }
 
-    fn draw_chunk_border(chunk_image: &mut DynamicImage, config: &MapConfig) {
-        let color = Color::GRAY;
+    /// Draws the border along the left and top edges of the chunk.
+    ///
+    /// The border keeps the same width in the world whatever size the texels are,
+    /// down to a single texel when each texel is a whole tile.
+    fn draw_chunk_border(data: &mut [u8], chunk_size: usize, tile_size: usize, config: &MapConfig) {
+        let color = Color::GRAY.as_rgba_u8();
+        let border_width = ((CHUNK_BORDER_WIDTH * tile_size) as f32 / config.tile_size) as usize;
+        let border_width = border_width.max(1).min(chunk_size);
+
+        for (row_index, image_row) in data
+            .chunks_exact_mut(chunk_size * BYTES_PER_PIXEL)
+            .enumerate()
+        {
+            let width = if row_index < border_width {
+                chunk_size
+            } else {
+                border_width
+            };
 
-        draw_filled_rect_mut(
-            chunk_image,
-            Rect::at(0, 0).of_size(5, config.chunk_size() as u32),
-            Rgba(color.as_rgba_u8()),
-        );
-        draw_filled_rect_mut(
-            chunk_image,
-            Rect::at(0, 0).of_size(config.chunk_size() as u32, 5),
-            Rgba(color.as_rgba_u8()),
-        );
+            fill(&mut image_row[..width * BYTES_PER_PIXEL], color);
+        }
     }
 
     /// Spawn & render a chunk with its given texture to the games map.
@@ -113,23 +236,18 @@ impl Chunk {
         texture: Handle<Image>,
         position: ChunkPosition,
     ) {
-        // Where in the map to to start rendering the chunk, based `position`
-        let horizontal_shift = config.chunk_size() * (position.x as f32 - 1.0);
-        let horizontal_start_pos =
-            (config.chunk_tile_count as f32 / 2.).mul_add(config.tile_size, horizontal_shift);
-        let vertical_shift = config.chunk_size() * (position.y as f32 - 1.0);
-        let vertical_start_pos =
-            (config.chunk_tile_count as f32 / 2.).mul_add(config.tile_size, vertical_shift);
+        let centre = position.centre(config);
 
         let chunk_id = commands
             .spawn((
                 SpriteBundle {
+                    // Stretch lower resolution textures to the full size of the chunk.
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::splat(config.chunk_size())),
+                        ..default()
+                    },
                     texture,
-                    transform: Transform::from_translation(Vec3 {
-                        x: horizontal_start_pos,
-                        y: vertical_start_pos,
-                        z: -1.,
-                    }),
+                    transform: Transform::from_translation(centre.extend(-1.)),
                     ..default()
                 },
                 self,
@@ -142,11 +260,70 @@ impl Chunk {
 
     /// Returns the tile type of all of the tiles if the chunk only contains one tile type.
     /// i.e. All ocean.
-    fn is_uniform_type(&self) -> Option<TileKind> {
-        TileKind::iter().find(|&tile_type| {
-            !self
+    fn is_uniform_type(&self) -> Option<TileId> {
+        let (&first, rest) = self.tiles.split_first()?;
+        rest.iter().all(|&id| id == first).then_some(TileId(first))
+    }
+}
+
+/// The pixels each tile in the [`TileRegistry`] is drawn with at a given tile size.
+struct TilePixels {
+    colors:   Vec<[u8; 4]>,
+    /// Textures scaled to the tile size, only used when tiles are more than a single pixel.
+    textures: Vec<Option<Vec<u8>>>,
+}
+
+impl TilePixels {
+    fn new(registry: &TileRegistry, tile_size: usize) -> Self {
+        Self {
+            colors:   registry
                 .iter()
-                .any(|row| row.iter().any(|tile| *tile != tile_type))
-        })
+                .map(|(_, tile)| tile.color.as_rgba_u8())
+                .collect(),
+            textures: registry
+                .iter()
+                .map(|(_, tile)| {
+                    tile.texture
+                        .as_ref()
+                        .filter(|_| tile_size > 1)
+                        .map(|texture| scale_texture(texture, tile_size))
+                })
+                .collect(),
+        }
+    }
+
+    fn color(&self, id: TileId) -> [u8; 4] {
+        self.colors
+            .get(usize::from(id.0))
+            .copied()
+            .unwrap_or_else(|| MISSING_TILE_COLOR.as_rgba_u8())
+    }
+
+    fn texture(&self, id: TileId) -> Option<&[u8]> {
+        self.textures.get(usize::from(id.0))?.as_deref()
+    }
+}
+
+/// Resizes a texture to a square of `size` pixels with nearest neighbour sampling.
+fn scale_texture(texture: &RgbaImage, size: usize) -> Vec<u8> {
+    let mut data = Vec::with_capacity(size * size * BYTES_PER_PIXEL);
+
+    for y in 0..size {
+        for x in 0..size {
+            let pixel = texture.get_pixel(
+                (x * texture.width() as usize / size) as u32,
+                (y * texture.height() as usize / size) as u32,
+            );
+            data.extend_from_slice(&pixel.0);
+        }
+    }
+
+    data
+}
+
+/// Sets every pixel in `pixels` to `color`.
+fn fill(pixels: &mut [u8], color: [u8; 4]) {
+    for pixel in pixels.chunks_exact_mut(BYTES_PER_PIXEL) {
+        pixel.copy_from_slice(&color);
     }
 }
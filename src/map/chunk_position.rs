@@ -1,8 +1,12 @@
+use std::str::FromStr;
+
 use bevy::ecs::component::Component;
+use bevy::math::{IVec2, UVec2, Vec2};
+use serde::{Deserialize, Serialize};
 
 use super::config::MapConfig;
 
-#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
 pub struct ChunkPosition {
     pub x: i32,
     pub y: i32,
@@ -15,4 +19,73 @@ impl ChunkPosition {
             y: (value.1 / map_config.chunk_size()) as i32,
         }
     }
+
+    /// Where the centre of the chunk is rendered in the world.
+    pub fn centre(self, map_config: &MapConfig) -> Vec2 {
+        // Chunks are rendered one chunk behind their position.
+        let horizontal_shift = map_config.chunk_size() * (self.x as f32 - 1.0);
+        let vertical_shift = map_config.chunk_size() * (self.y as f32 - 1.0);
+        let half_chunk = map_config.chunk_tile_count as f32 / 2.;
+
+        Vec2::new(
+            half_chunk.mul_add(map_config.tile_size, horizontal_shift),
+            half_chunk.mul_add(map_config.tile_size, vertical_shift),
+        )
+    }
+
+    /// World tile coordinates of the bottom left tile of the chunk.
+    pub const fn first_tile(self, map_config: &MapConfig) -> IVec2 {
+        let chunk_tile_count = map_config.chunk_tile_count as i32;
+
+        // Chunks are rendered one chunk behind their position, see `ChunkPosition::centre`.
+        IVec2::new(
+            (self.x - 1) * chunk_tile_count,
+            (self.y - 1) * chunk_tile_count,
+        )
+    }
+
+    /// Finds the chunk a tile is in from its world tile coordinates,
+    /// along with the tiles column and row within that chunk.
+    pub fn from_tile(tile: IVec2, map_config: &MapConfig) -> (Self, UVec2) {
+        let chunk_tile_count = map_config.chunk_tile_count as i32;
+
+        // Chunks are rendered one chunk behind their position, see `ChunkPosition::centre`.
+        let position = Self {
+            x: tile.x.div_euclid(chunk_tile_count) + 1,
+            y: tile.y.div_euclid(chunk_tile_count) + 1,
+        };
+        let local = tile.rem_euclid(IVec2::splat(chunk_tile_count)).as_uvec2();
+
+        (position, local)
+    }
+
+    /// The equivalent chunk within the [`WorldShape::wrap_area`](super::world_shape::WorldShape::wrap_area)
+    /// if the world wraps.
+    pub fn wrap(self, map_config: &MapConfig) -> Self {
+        let first_tile = map_config
+            .world_shape
+            .wrap_tile(self.first_tile(map_config), map_config.chunk_tile_count);
+
+        Self::from_tile(first_tile, map_config).0
+    }
+}
+
+impl From<ChunkPosition> for IVec2 {
+    fn from(value: ChunkPosition) -> Self { Self::new(value.x, value.y) }
+}
+
+/// Parses chunk coordinates written as `x,y`, such as on the command line.
+impl FromStr for ChunkPosition {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (x, y) = value
+            .split_once(',')
+            .ok_or_else(|| format!("expected `x,y`, got `{value}`"))?;
+
+        Ok(Self {
+            x: x.trim().parse().map_err(|e| format!("invalid x: {e}"))?,
+            y: y.trim().parse().map_err(|e| format!("invalid y: {e}"))?,
+        })
+    }
 }
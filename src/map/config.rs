@@ -1,9 +1,13 @@
 use bevy::prelude::*;
 
+use super::heightmap::HeightmapTerrain;
+use super::stamp::Stamp;
+use super::world_shape::WorldShape;
+
 const TILE_SIZE: f32 = 15.0;
 const CHUNK_TILE_COUNT: usize = 20;
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct MapConfig {
     /// Size of a tile in pixels.
     pub tile_size:        f32,
@@ -11,6 +15,14 @@ pub struct MapConfig {
     pub chunk_tile_count: usize,
     /// The seed for the noisemap.
     pub seed:             u64,
+    /// Draw chunk textures with a single pixel per tile, leaving the sprite to scale them up.
+    pub pixel_per_tile:   bool,
+    /// Whether the world is endless or a bounded island.
+    pub world_shape:      WorldShape,
+    /// Hand made terrain used instead of noise where it covers the world.
+    pub heightmap:        Option<HeightmapTerrain>,
+    /// Hand made areas placed over the terrain.
+    pub stamps:           Vec<Stamp>,
 }
 
 impl MapConfig {
@@ -24,6 +36,10 @@ impl Default for MapConfig {
             tile_size:        TILE_SIZE,
             chunk_tile_count: CHUNK_TILE_COUNT,
             seed:             0,
+            pixel_per_tile:   false,
+            world_shape:      WorldShape::Infinite,
+            heightmap:        None,
+            stamps:           Vec::new(),
         }
     }
 }
@@ -0,0 +1,76 @@
+//! Hand made terrain from greyscale images.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use image::GrayImage;
+
+/// Where the game looks for a heightmap, the world is fully procedural if there isn't one.
+pub const HEIGHTMAP_PATH: &str = "assets/heightmap.png";
+
+/// Terrain heights read from a greyscale image, one pixel per tile.
+///
+/// The centre of the image is placed at the origin with the top of the image facing up the map.
+/// Black is the lowest terrain height and white the highest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightmap {
+    size:    UVec2,
+    /// Rows of heights from the top of the image.
+    heights: Vec<f64>,
+}
+
+impl Heightmap {
+    pub fn from_image(image: &GrayImage) -> Self {
+        Self {
+            size:    UVec2::new(image.width(), image.height()),
+            heights: image
+                .pixels()
+                .map(|pixel| (f64::from(pixel.0[0]) / f64::from(u8::MAX)).mul_add(2.0, -1.0))
+                .collect(),
+        }
+    }
+
+    /// Loads a heightmap from an image, converting it to greyscale.
+    ///
+    /// # Errors
+    ///
+    /// If the image can't be read.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let image = image::open(path).map_err(|e| e.to_string())?;
+        Ok(Self::from_image(&image.to_luma8()))
+    }
+
+    /// Size of the heightmap in tiles.
+    pub const fn size(&self) -> UVec2 { self.size }
+
+    /// The terrain height of a tile from its world tile coordinates, if it is within the image.
+    pub fn get(&self, tile: IVec2) -> Option<f64> {
+        let size = self.size.as_ivec2();
+        // Image rows go top to bottom, while tile rows go bottom to top.
+        let pixel = IVec2::new(tile.x + size.x / 2, size.y - 1 - (tile.y + size.y / 2));
+
+        if pixel.cmplt(IVec2::ZERO).any() || pixel.cmpge(size).any() {
+            return None;
+        }
+
+        Some(self.heights[(pixel.y * size.x + pixel.x) as usize])
+    }
+}
+
+/// What the terrain is outside of a [`Heightmap`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutsideHeightmap {
+    /// Carry on with procedural terrain.
+    #[default]
+    Procedural,
+    /// Nothing but ocean.
+    Ocean,
+}
+
+/// A [`Heightmap`] used as the terrain instead of noise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeightmapTerrain {
+    pub heightmap: Arc<Heightmap>,
+    pub outside:   OutsideHeightmap,
+}
@@ -0,0 +1,127 @@
+//! Noise the terrain is generated from.
+
+use bevy::prelude::*;
+use worldgen::noise::perlin::PerlinNoise;
+use worldgen::noise::NoiseProvider;
+use worldgen::noisemap::Seed;
+
+use super::chunk_position::ChunkPosition;
+use super::config::MapConfig;
+use super::heightmap::{HeightmapTerrain, OutsideHeightmap};
+use super::world_shape::{WorldShape, OCEAN_HEIGHT};
+
+/// Distance in noise space between neighbouring tiles.
+const NOISE_STEP: f64 = 0.01;
+
+/// Generates the noise values tiles are created from.
+///
+/// Noise is sampled from the world coordinates of each tile, so the same tile always gets the same
+/// value no matter the chunk size or tile size, and neighbouring chunks line up seamlessly.
+#[derive(Resource, Clone)]
+pub struct NoiseMap {
+    noise:     PerlinNoise,
+    seed:      u64,
+    shape:     WorldShape,
+    /// The tiles that repeat, if the world wraps.
+    wrap:      Option<IRect>,
+    heightmap: Option<HeightmapTerrain>,
+}
+
+impl NoiseMap {
+    pub fn new(config: &MapConfig) -> Self {
+        Self {
+            noise:     PerlinNoise::new(),
+            seed:      Seed::of(config.seed).value,
+            shape:     config.world_shape,
+            wrap:      config.world_shape.wrap_area(config.chunk_tile_count),
+            heightmap: config.heightmap.clone(),
+        }
+    }
+
+    /// Noise value of a single tile, from its world tile coordinates.
+    pub fn sample(&self, tile: IVec2) -> f64 {
+        let height = self.wrap.map_or_else(
+            || self.sample_raw(tile),
+            |area| self.sample_seamless(tile, area),
+        );
+
+        self.shape.apply(tile, height)
+    }
+
+    /// Height of a tile from the heightmap if it covers the tile, otherwise from noise.
+    fn sample_raw(&self, tile: IVec2) -> f64 {
+        if let Some(terrain) = &self.heightmap {
+            if let Some(height) = terrain.heightmap.get(tile) {
+                return height;
+            }
+            if terrain.outside == OutsideHeightmap::Ocean {
+                return OCEAN_HEIGHT;
+            }
+        }
+
+        self.noise.generate(
+            f64::from(tile.x) * NOISE_STEP,
+            f64::from(tile.y) * NOISE_STEP,
+            self.seed,
+        )
+    }
+
+    /// Samples noise that repeats every `area`, by blending the noise at the tile with the noise
+    /// one area to the left and below it, weighted by how far across the area the tile is.
+    fn sample_seamless(&self, tile: IVec2, area: IRect) -> f64 {
+        let size = area.size();
+        let local = (tile - area.min).rem_euclid(size);
+        let tile = area.min + local;
+        let across = local.as_dvec2() / size.as_dvec2();
+
+        let samples = [
+            (self.sample_raw(tile), (1.0 - across.x) * (1.0 - across.y)),
+            (
+                self.sample_raw(tile - IVec2::new(size.x, 0)),
+                across.x * (1.0 - across.y),
+            ),
+            (
+                self.sample_raw(tile - IVec2::new(0, size.y)),
+                (1.0 - across.x) * across.y,
+            ),
+            (self.sample_raw(tile - size), across.x * across.y),
+        ];
+
+        // Blending flattens the noise towards the middle of the area,
+        // so scale it back up to keep the same spread of tiles.
+        let blended: f64 = samples.iter().map(|(height, weight)| height * weight).sum();
+        let spread: f64 = samples.iter().map(|(_, weight)| weight * weight).sum();
+
+        blended / spread.sqrt()
+    }
+
+    /// Noise values for a rectangle of tiles starting at `first_tile`,
+    /// as rows going up from the bottom of the area.
+    pub fn generate_area(&self, first_tile: IVec2, size: UVec2) -> Vec<Vec<f64>> {
+        (0..size.y as i32)
+            .map(|row| {
+                (0..size.x as i32)
+                    .map(|column| self.sample(first_tile + IVec2::new(column, row)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Noise values for every tile of a chunk, as rows going up from the bottom of the chunk.
+    pub fn generate_chunk(&self, position: ChunkPosition, config: &MapConfig) -> Vec<Vec<f64>> {
+        self.generate_area(
+            position.first_tile(config),
+            UVec2::splat(config.chunk_tile_count as u32),
+        )
+    }
+}
+
+impl FromWorld for NoiseMap {
+    fn from_world(world: &mut World) -> Self {
+        let config = world
+            .get_resource::<MapConfig>()
+            .expect("NoiseMap must be initialised before MapConfig");
+
+        Self::new(config)
+    }
+}
@@ -0,0 +1,123 @@
+//! Rendering areas of the map outside of the game, such as for exporting previews.
+
+use bevy::math::UVec2;
+use image::RgbaImage;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use super::chunk::Chunk;
+use super::chunk_position::ChunkPosition;
+use super::config::MapConfig;
+use super::noise::NoiseMap;
+use super::tile_registry::TileRegistry;
+use super::tiled::TiledMap;
+use super::{ChunkBorderState, ChunkLod};
+
+/// An inclusive rectangle of chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkRect {
+    pub min: ChunkPosition,
+    pub max: ChunkPosition,
+}
+
+impl ChunkRect {
+    /// Creates a [`ChunkRect`] between two corners, in any order.
+    pub fn new(a: ChunkPosition, b: ChunkPosition) -> Self {
+        Self {
+            min: ChunkPosition {
+                x: a.x.min(b.x),
+                y: a.y.min(b.y),
+            },
+            max: ChunkPosition {
+                x: a.x.max(b.x),
+                y: a.y.max(b.y),
+            },
+        }
+    }
+
+    /// Width of the rectangle in chunks.
+    pub const fn width(&self) -> u32 { (self.max.x - self.min.x + 1) as u32 }
+
+    /// Height of the rectangle in chunks.
+    pub const fn height(&self) -> u32 { (self.max.y - self.min.y + 1) as u32 }
+
+    /// Every chunk position within the rectangle.
+    pub fn positions(&self) -> impl Iterator<Item = ChunkPosition> + '_ {
+        (self.min.y..=self.max.y)
+            .flat_map(move |y| (self.min.x..=self.max.x).map(move |x| ChunkPosition { x, y }))
+    }
+}
+
+/// Generates every chunk in `rect` and draws them into a single image,
+/// using the same textures chunks get in game.
+///
+/// Doesnt require a window or GPU.
+pub fn render_region(
+    config: &MapConfig,
+    registry: &TileRegistry,
+    rect: ChunkRect,
+    chunk_borders: ChunkBorderState,
+) -> RgbaImage {
+    let noisemap = NoiseMap::new(config);
+
+    let textures: Vec<_> = rect
+        .positions()
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|position| {
+            let chunk = Chunk::new(position, &noisemap, config, registry);
+            let texture =
+                chunk.generate_texture_image(config, registry, chunk_borders, ChunkLod::default());
+            (position, texture)
+        })
+        .collect();
+
+    let chunk_size = ChunkLod::default().tile_pixels(config) * config.chunk_tile_count as u32;
+    let mut region = RgbaImage::new(rect.width() * chunk_size, rect.height() * chunk_size);
+
+    for (position, texture) in textures {
+        // Chunks further up the map go towards the top of the image.
+        let left = (position.x - rect.min.x) as u32 * chunk_size;
+        let top = (rect.max.y - position.y) as u32 * chunk_size;
+
+        for (index, pixel) in texture.data.chunks_exact(4).enumerate() {
+            let x = left + index as u32 % chunk_size;
+            let y = top + index as u32 / chunk_size;
+            region.put_pixel(x, y, image::Rgba([pixel[0], pixel[1], pixel[2], pixel[3]]));
+        }
+    }
+
+    region
+}
+
+/// Generates every chunk in `rect` as a Tiled map, with each tile id being its
+/// [`TileId`](super::tile_registry::TileId).
+///
+/// Stamping the map back at `rect.min` gives the same tiles.
+pub fn region_tiled_map(config: &MapConfig, registry: &TileRegistry, rect: ChunkRect) -> TiledMap {
+    let noisemap = NoiseMap::new(config);
+    let chunk_tile_count = config.chunk_tile_count as u32;
+
+    let chunks: Vec<_> = rect
+        .positions()
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|position| (position, Chunk::new(position, &noisemap, config, registry)))
+        .collect();
+
+    let size = UVec2::new(rect.width(), rect.height()) * chunk_tile_count;
+    let mut tiles = vec![None; (size.x * size.y) as usize];
+
+    for (position, chunk) in chunks {
+        let left = (position.x - rect.min.x) as u32 * chunk_tile_count;
+        let bottom = (position.y - rect.min.y) as u32 * chunk_tile_count;
+
+        for (local, tile) in chunk.iter() {
+            // Tiled rows go top to bottom, while tile rows go bottom to top.
+            let x = left + local.x;
+            let y = size.y - 1 - (bottom + local.y);
+            tiles[(y * size.x + x) as usize] = Some(u32::from(tile.0));
+        }
+    }
+
+    TiledMap { size, tiles }
+}
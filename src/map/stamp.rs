@@ -0,0 +1,139 @@
+//! Hand made areas placed into the generated world, such as towns and tutorial areas.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+use super::chunk::Chunk;
+use super::chunk_position::ChunkPosition;
+use super::config::MapConfig;
+use super::tile_registry::{TileId, TileRegistry};
+use super::tiled::TiledMap;
+
+/// Where the game loads its stamps from.
+pub const STAMPS_PATH: &str = "assets/stamps.ron";
+
+/// A hand made area of tiles that replaces the generated terrain where it is placed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stamp {
+    /// The chunk the bottom left corner of the stamp is placed in.
+    pub position: ChunkPosition,
+    size:         UVec2,
+    /// Rows of tiles from the bottom of the stamp, `None` where the generated terrain is kept.
+    tiles:        Arc<[Option<TileId>]>,
+}
+
+/// A stamp as written in the stamps file.
+#[derive(Deserialize)]
+struct StampData {
+    /// Tiled map, relative to the stamps file.
+    map:      String,
+    position: ChunkPosition,
+    /// Tiled tile ids to tile names, ids that aren't listed are used as registry ids.
+    #[serde(default)]
+    tiles:    HashMap<u32, String>,
+}
+
+impl Stamp {
+    /// Creates a stamp from a Tiled map.
+    ///
+    /// Each tile id is looked up in `mapping`, falling back to the tile with the same id in the registry.
+    ///
+    /// # Errors
+    ///
+    /// If the map uses a tile id that isn't in `mapping` or the registry.
+    pub fn from_tiled(
+        map: &TiledMap,
+        position: ChunkPosition,
+        mapping: &HashMap<u32, TileId>,
+        registry: &TileRegistry,
+    ) -> Result<Self, String> {
+        let mut tiles = Vec::with_capacity(map.tiles.len());
+
+        // Tiled rows go top to bottom, while tile rows go bottom to top.
+        for y in (0..map.size.y).rev() {
+            for x in 0..map.size.x {
+                let tile = map
+                    .get(x, y)
+                    .map(|id| {
+                        mapping
+                            .get(&id)
+                            .copied()
+                            .or_else(|| u8::try_from(id).ok().map(TileId))
+                            .filter(|tile| registry.get(*tile).is_some())
+                            .ok_or_else(|| format!("tile id {id} has no matching tile"))
+                    })
+                    .transpose()?;
+                tiles.push(tile);
+            }
+        }
+
+        Ok(Self {
+            position,
+            size: map.size,
+            tiles: tiles.into(),
+        })
+    }
+
+    /// Size of the stamp in tiles.
+    pub const fn size(&self) -> UVec2 { self.size }
+
+    /// Overwrites the tiles of a chunk that are covered by the stamp.
+    pub fn apply(&self, position: ChunkPosition, chunk: &mut Chunk, config: &MapConfig) {
+        let chunk_first_tile = position.first_tile(config);
+        let stamp_first_tile = self.position.first_tile(config);
+
+        let min = chunk_first_tile.max(stamp_first_tile);
+        let max = (chunk_first_tile + IVec2::splat(chunk.size() as i32))
+            .min(stamp_first_tile + self.size.as_ivec2());
+
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let tile = IVec2::new(x, y);
+                let stamp_local = (tile - stamp_first_tile).as_uvec2();
+
+                if let Some(id) = self.tiles[(stamp_local.y * self.size.x + stamp_local.x) as usize]
+                {
+                    chunk.set((tile - chunk_first_tile).as_uvec2(), id);
+                }
+            }
+        }
+    }
+}
+
+/// Loads every stamp from a stamps file, with their maps relative to it.
+///
+/// # Errors
+///
+/// If the file or any of its maps can't be loaded.
+pub fn load_stamps(path: &Path, registry: &TileRegistry) -> Result<Vec<Stamp>, String> {
+    let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let stamps: Vec<StampData> = ron::from_str(&data).map_err(|e| e.to_string())?;
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    stamps
+        .into_iter()
+        .map(|stamp| {
+            let mapping = stamp
+                .tiles
+                .iter()
+                .map(|(&id, name)| {
+                    registry
+                        .find(name)
+                        .map(|tile| (id, tile))
+                        .ok_or_else(|| format!("{}: unknown tile `{name}`", stamp.map))
+                })
+                .collect::<Result<_, String>>()?;
+
+            let map =
+                TiledMap::load(&dir.join(&stamp.map)).map_err(|e| format!("{}: {e}", stamp.map))?;
+
+            Stamp::from_tiled(&map, stamp.position, &mapping, registry)
+                .map_err(|e| format!("{}: {e}", stamp.map))
+        })
+        .collect()
+}
@@ -0,0 +1,201 @@
+//! The kinds of tiles in the world, loaded from data rather than defined in code.
+
+use std::fs;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+/// Where the game loads its tiles from.
+pub const TILES_PATH: &str = "assets/tiles.ron";
+
+/// The tiles the game ships with, used when [`TILES_PATH`] can't be loaded.
+const DEFAULT_TILES: &str = include_str!("../../assets/tiles.ron");
+
+/// Drawn for tile ids that aren't in the registry, such as from a save made with other tiles.
+pub const MISSING_TILE_COLOR: Color = Color::FUCHSIA;
+
+/// Identifies a tile in the [`TileRegistry`]. This is what chunks store for each tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TileId(pub u8);
+
+/// Everything known about a kind of tile.
+#[derive(Debug, Clone)]
+pub struct TileDef {
+    pub name:     String,
+    pub color:    Color,
+    /// Whether the tile is dry land rather than water.
+    pub land:     bool,
+    /// Whether characters can move onto the tile. Only recorded for now, the player isn't stopped by it.
+    pub walkable: bool,
+    /// Multiplier for how fast the player moves across the tile.
+    pub speed:    f32,
+    /// Whether the player swims rather than walks across the tile.
+    pub swimming: bool,
+    /// Drawn over the tile instead of its colour, when tiles are large enough to show it.
+    pub texture:  Option<RgbaImage>,
+    /// Terrain height above which the tile is generated, `None` if the terrain never generates it.
+    pub height:   Option<f64>,
+}
+
+/// A tile as written in the tiles file.
+#[derive(Deserialize)]
+struct TileData {
+    name:     String,
+    colour:   String,
+    #[serde(default = "default_land")]
+    land:     bool,
+    #[serde(default = "default_walkable")]
+    walkable: bool,
+    #[serde(default = "default_speed")]
+    speed:    f32,
+    #[serde(default)]
+    swimming: bool,
+    #[serde(default)]
+    texture:  Option<String>,
+    #[serde(default)]
+    height:   Option<f64>,
+}
+
+const fn default_land() -> bool { true }
+
+const fn default_walkable() -> bool { true }
+
+const fn default_speed() -> f32 { 1.0 }
+
+/// Every kind of tile, indexed by [`TileId`].
+#[derive(Resource, Debug, Clone)]
+pub struct TileRegistry {
+    tiles:   Vec<TileDef>,
+    /// Tiles the terrain generates, from highest to lowest height.
+    terrain: Vec<(f64, TileId)>,
+}
+
+impl TileRegistry {
+    /// Creates a registry where each tile's id is its position in `tiles`.
+    ///
+    /// # Errors
+    ///
+    /// If there are more than 256 tiles, names are repeated or no tiles are generated by the terrain.
+    pub fn new(tiles: Vec<TileDef>) -> Result<Self, String> {
+        if tiles.len() > usize::from(u8::MAX) + 1 {
+            return Err(format!(
+                "{} tiles is more than the limit of 256",
+                tiles.len()
+            ));
+        }
+
+        let mut names = HashSet::new();
+        if let Some(tile) = tiles
+            .iter()
+            .find(|tile| !names.insert(tile.name.to_ascii_lowercase()))
+        {
+            return Err(format!("tile `{}` is defined more than once", tile.name));
+        }
+
+        let mut terrain: Vec<_> = tiles
+            .iter()
+            .enumerate()
+            .filter_map(|(id, tile)| Some((tile.height?, TileId(id as u8))))
+            .collect();
+        if terrain.is_empty() {
+            return Err("no tiles have a height for the terrain to generate".to_string());
+        }
+        terrain.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        Ok(Self { tiles, terrain })
+    }
+
+    /// Parses a registry from the contents of a tiles file, loading textures relative to `dir`.
+    ///
+    /// # Errors
+    ///
+    /// If the data or a texture is invalid, or the tiles can't make a registry.
+    pub fn from_ron(data: &str, dir: &Path) -> Result<Self, String> {
+        let tiles: Vec<TileData> = ron::from_str(data).map_err(|e| e.to_string())?;
+
+        let tiles = tiles
+            .into_iter()
+            .map(|tile| {
+                let color = Color::hex(&tile.colour)
+                    .map_err(|e| format!("tile `{}` has an invalid colour: {e}", tile.name))?;
+                let texture = tile
+                    .texture
+                    .map(|texture| {
+                        image::open(dir.join(&texture))
+                            .map(|image| image.to_rgba8())
+                            .map_err(|e| format!("tile `{}` texture {texture}: {e}", tile.name))
+                    })
+                    .transpose()?;
+
+                Ok(TileDef {
+                    name: tile.name,
+                    color,
+                    land: tile.land,
+                    walkable: tile.walkable,
+                    speed: tile.speed,
+                    swimming: tile.swimming,
+                    texture,
+                    height: tile.height,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Self::new(tiles)
+    }
+
+    /// Loads a registry from a tiles file.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be read or [`TileRegistry::from_ron`] fails.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_ron(&data, path.parent().unwrap_or_else(|| Path::new("")))
+    }
+
+    /// Gets the definition of a tile, if it exists.
+    pub fn get(&self, id: TileId) -> Option<&TileDef> { self.tiles.get(usize::from(id.0)) }
+
+    /// Finds a tile by name, ignoring case.
+    pub fn find(&self, name: &str) -> Option<TileId> {
+        self.tiles
+            .iter()
+            .position(|tile| tile.name.eq_ignore_ascii_case(name))
+            .map(|id| TileId(id as u8))
+    }
+
+    /// Every tile with its id.
+    pub fn iter(&self) -> impl Iterator<Item = (TileId, &TileDef)> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(id, tile)| (TileId(id as u8), tile))
+    }
+
+    /// The colour a tile is drawn with, [`MISSING_TILE_COLOR`] if it doesn't exist.
+    pub fn color(&self, id: TileId) -> Color {
+        self.get(id).map_or(MISSING_TILE_COLOR, |tile| tile.color)
+    }
+
+    /// The tile the terrain generates at a height of the terrain noise.
+    ///
+    /// Picks the tile with the highest height below `height`, or the lowest tile if there are none.
+    pub fn terrain(&self, height: f64) -> TileId {
+        self.terrain
+            .iter()
+            .find(|(min, _)| height > *min)
+            .or_else(|| self.terrain.last())
+            .map_or(TileId(0), |(_, id)| *id)
+    }
+}
+
+/// The tiles the game ships with.
+impl Default for TileRegistry {
+    fn default() -> Self {
+        Self::from_ron(DEFAULT_TILES, Path::new("assets"))
+            .expect("The default tiles should always be valid")
+    }
+}
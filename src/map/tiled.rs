@@ -0,0 +1,265 @@
+//! Reading and writing maps for the [Tiled](https://www.mapeditor.org/) map editor.
+
+use std::fmt::Write;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use bevy::prelude::*;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use image::{Rgba, RgbaImage};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::tile_registry::TileRegistry;
+
+/// The high bits of a Tiled global tile id are used for flipping and rotating the tile.
+const TILE_ID_MASK: u32 = 0x0FFF_FFFF;
+
+/// The tiles of a Tiled map, with every tile layer merged together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiledMap {
+    /// Size of the map in tiles.
+    pub size:  UVec2,
+    /// The id of each tile within the map's tileset, as rows from the top of the map.
+    /// `None` where no layer has a tile.
+    pub tiles: Vec<Option<u32>>,
+}
+
+impl TiledMap {
+    /// Loads a `.tmx` map.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be read or [`TiledMap::parse`] fails.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let xml = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&xml)
+    }
+
+    /// Parses the contents of a `.tmx` map.
+    ///
+    /// Supports finite orthogonal maps with a single tileset,
+    /// and CSV or base64 layer data, optionally zlib or gzip compressed.
+    ///
+    /// # Errors
+    ///
+    /// If the map is invalid or uses features that aren't supported.
+    pub fn parse(xml: &str) -> Result<Self, String> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut size = None;
+        let mut first_id = None;
+        let mut layers = Vec::new();
+        // Encoding and compression of the layer data being read.
+        let mut data: Option<(String, Option<String>)> = None;
+
+        loop {
+            let event = reader.read_event().map_err(|e| e.to_string())?;
+            match &event {
+                Event::Start(element) | Event::Empty(element) => match element.name().as_ref() {
+                    b"map" => {
+                        if attribute(element, "infinite")?.as_deref() == Some("1") {
+                            return Err("infinite maps are not supported".to_string());
+                        }
+                        size = Some(UVec2::new(
+                            parse_attribute(element, "width")?,
+                            parse_attribute(element, "height")?,
+                        ));
+                    }
+                    b"tileset" => {
+                        // Tile ids are kept within their tileset, so they would collide between tilesets.
+                        if first_id.is_some() {
+                            return Err("maps with several tilesets are not supported".to_string());
+                        }
+                        first_id = Some(parse_attribute::<u32>(element, "firstgid")?);
+                    }
+                    b"data" => {
+                        // XML layer data has a <tile> element for each tile, rather than text.
+                        let encoding = attribute(element, "encoding")?
+                            .ok_or("XML layer data is not supported, use CSV or base64")?;
+                        if matches!(event, Event::Start(_)) {
+                            data = Some((encoding, attribute(element, "compression")?));
+                        }
+                    }
+                    b"chunk" => return Err("infinite maps are not supported".to_string()),
+                    _ => {}
+                },
+                Event::Text(text) => {
+                    if let Some((encoding, compression)) = &data {
+                        let text = text.unescape().map_err(|e| e.to_string())?;
+                        layers.push(decode_layer(&text, encoding, compression.as_deref())?);
+                    }
+                }
+                Event::End(element) if element.name().as_ref() == b"data" => data = None,
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        let size = size.ok_or("missing <map> element")?;
+        let tile_count = (size.x * size.y) as usize;
+
+        let mut tiles = vec![None; tile_count];
+        for layer in layers {
+            if layer.len() != tile_count {
+                return Err(format!(
+                    "layer has {} tiles but the map is {}x{}",
+                    layer.len(),
+                    size.x,
+                    size.y
+                ));
+            }
+
+            // Later layers are drawn over earlier ones.
+            for (tile, gid) in tiles.iter_mut().zip(layer) {
+                let gid = gid & TILE_ID_MASK;
+                if gid == 0 {
+                    continue;
+                }
+
+                let first_id = first_id
+                    .filter(|&first_id| first_id <= gid)
+                    .ok_or_else(|| format!("tile {gid} isn't in the tileset"))?;
+                *tile = Some(gid - first_id);
+            }
+        }
+
+        Ok(Self { size, tiles })
+    }
+
+    /// The tile at a column and row from the top left of the map.
+    pub fn get(&self, x: u32, y: u32) -> Option<u32> {
+        if x >= self.size.x || y >= self.size.y {
+            return None;
+        }
+
+        self.tiles[(y * self.size.x + x) as usize]
+    }
+
+    /// Writes the map as a `.tmx` map with a single CSV layer,
+    /// where each tile id is the [`TileId`](super::tile_registry::TileId) in `registry`.
+    ///
+    /// `tileset_image` is the path of the [`tileset_image`] relative to the map,
+    /// with tiles `tile_pixels` wide and high.
+    pub fn to_tmx(&self, registry: &TileRegistry, tileset_image: &str, tile_pixels: u32) -> String {
+        let tile_count = registry.iter().count() as u32;
+        let (width, height) = (self.size.x, self.size.y);
+
+        // Name each tile so they can be told apart in Tiled.
+        let tile_names = registry.iter().fold(String::new(), |mut names, (id, tile)| {
+            // Writing to a `String` can't fail.
+            let _ = writeln!(
+                names,
+                r#"  <tile id="{}"><properties><property name="name" value="{}"/></properties></tile>"#,
+                id.0,
+                escape(&tile.name)
+            );
+            names
+        });
+
+        // Tiled leaves a trailing comma on every row but the last.
+        let data = self
+            .tiles
+            .chunks(width as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|tile| tile.map_or(0, |id| id + 1).to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" renderorder="right-down" width="{width}" height="{height}" tilewidth="{tile_pixels}" tileheight="{tile_pixels}" infinite="0" nextlayerid="2" nextobjectid="1">
+ <tileset firstgid="1" name="tiles" tilewidth="{tile_pixels}" tileheight="{tile_pixels}" tilecount="{tile_count}" columns="{tile_count}">
+  <image source="{image}" width="{image_width}" height="{tile_pixels}"/>
+{tile_names} </tileset>
+ <layer id="1" name="Terrain" width="{width}" height="{height}">
+  <data encoding="csv">
+{data}
+</data>
+ </layer>
+</map>
+"#,
+            image = escape(tileset_image),
+            image_width = tile_count * tile_pixels,
+        )
+    }
+}
+
+/// A tileset for [`TiledMap::to_tmx`], a single row with a square of each tile's colour.
+pub fn tileset_image(registry: &TileRegistry, tile_pixels: u32) -> RgbaImage {
+    let tiles: Vec<_> = registry.iter().map(|(id, _)| registry.color(id)).collect();
+
+    RgbaImage::from_fn(tiles.len() as u32 * tile_pixels, tile_pixels, |x, _| {
+        Rgba(tiles[(x / tile_pixels) as usize].as_rgba_u8())
+    })
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>, String> {
+    element
+        .try_get_attribute(name)
+        .map_err(|e| e.to_string())?
+        .map(|value| {
+            value
+                .unescape_value()
+                .map(std::borrow::Cow::into_owned)
+                .map_err(|e| e.to_string())
+        })
+        .transpose()
+}
+
+fn parse_attribute<T: std::str::FromStr>(element: &BytesStart, name: &str) -> Result<T, String> {
+    attribute(element, name)?
+        .ok_or_else(|| format!("missing `{name}` attribute"))?
+        .parse()
+        .map_err(|_| format!("invalid `{name}` attribute"))
+}
+
+/// Decodes the global tile ids of a layer.
+fn decode_layer(text: &str, encoding: &str, compression: Option<&str>) -> Result<Vec<u32>, String> {
+    match encoding {
+        "csv" => text
+            .split(',')
+            .map(|gid| {
+                gid.trim()
+                    .parse()
+                    .map_err(|_| format!("invalid tile `{}`", gid.trim()))
+            })
+            .collect(),
+        "base64" => {
+            let compressed = BASE64.decode(text.trim()).map_err(|e| e.to_string())?;
+
+            let mut bytes = Vec::new();
+            match compression {
+                None => bytes = compressed,
+                Some("zlib") => {
+                    ZlibDecoder::new(compressed.as_slice())
+                        .read_to_end(&mut bytes)
+                        .map_err(|e| e.to_string())?;
+                }
+                Some("gzip") => {
+                    GzDecoder::new(compressed.as_slice())
+                        .read_to_end(&mut bytes)
+                        .map_err(|e| e.to_string())?;
+                }
+                Some(compression) => {
+                    return Err(format!("{compression} compression is not supported"));
+                }
+            }
+
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+                .collect())
+        }
+        encoding => Err(format!("{encoding} encoding is not supported")),
+    }
+}
@@ -0,0 +1,120 @@
+//! The overall shape of the world, either endless, a bounded island or wrapping around.
+
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::config::MapConfig;
+
+/// Terrain height at and past the edge of an island, low enough to always be the lowest tile.
+pub(super) const OCEAN_HEIGHT: f64 = -1.0;
+/// How far from the centre of an island, as a fraction of its radius, the terrain starts falling away.
+const FALLOFF_START: f64 = 0.6;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorldShape {
+    /// Terrain goes on forever.
+    #[default]
+    Infinite,
+    /// A bounded world `radius` tiles around the origin,
+    /// with the terrain falling away towards its edges and ocean past them.
+    Island { radius: u32, falloff: Falloff },
+    /// The world repeats every `width` by `height` chunks,
+    /// walking off one edge brings you back in at the opposite one.
+    Wrapped { width: u32, height: u32 },
+}
+
+/// How the distance to the edge of an island is measured.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Falloff {
+    /// A round island.
+    #[default]
+    Radial,
+    /// A square island.
+    Square,
+}
+
+impl Falloff {
+    /// Distance of `point` from the origin.
+    fn distance(self, point: DVec2) -> f64 {
+        match self {
+            Self::Radial => point.length(),
+            Self::Square => point.abs().max_element(),
+        }
+    }
+}
+
+impl WorldShape {
+    /// Lowers the terrain `height` of a tile depending on how close it is to the edge of the world.
+    pub fn apply(self, tile: IVec2, height: f64) -> f64 {
+        let Self::Island { radius, falloff } = self else {
+            return height;
+        };
+
+        // Measured from the centre of the tile.
+        let distance = falloff.distance(tile.as_dvec2() + 0.5) / f64::from(radius.max(1));
+        let edge = ((distance - FALLOFF_START) / (1.0 - FALLOFF_START)).clamp(0.0, 1.0);
+        // Smoothstep, so the terrain eases into the ocean rather than having a visible ring.
+        let edge = edge * edge * 2.0f64.mul_add(-edge, 3.0);
+
+        (OCEAN_HEIGHT - height).mul_add(edge, height)
+    }
+
+    /// The tiles that repeat in a wrapped world, centred on the origin and lined up with chunks.
+    pub fn wrap_area(self, chunk_tile_count: usize) -> Option<IRect> {
+        let Self::Wrapped { width, height } = self else {
+            return None;
+        };
+
+        let chunks = IVec2::new(width.max(1) as i32, height.max(1) as i32);
+        let min = -(chunks / 2) * chunk_tile_count as i32;
+
+        Some(IRect::from_corners(
+            min,
+            min + chunks * chunk_tile_count as i32,
+        ))
+    }
+
+    /// Moves world tile coordinates into the [`wrap_area`](Self::wrap_area) if the world wraps.
+    pub fn wrap_tile(self, tile: IVec2, chunk_tile_count: usize) -> IVec2 {
+        self.wrap_area(chunk_tile_count).map_or(tile, |area| {
+            area.min + (tile - area.min).rem_euclid(area.size())
+        })
+    }
+
+    /// Moves a world position into the [`wrap_area`](Self::wrap_area) if the world wraps,
+    /// the equivalent position to teleport to when walking off an edge.
+    pub fn wrap(self, position: Vec2, config: &MapConfig) -> Vec2 {
+        self.wrap_area(config.chunk_tile_count)
+            .map_or(position, |area| {
+                let min = area.min.as_vec2() * config.tile_size;
+                let size = area.size().as_vec2() * config.tile_size;
+                min + (position - min).rem_euclid(size)
+            })
+    }
+
+    /// The position equivalent to `position` closest to `target`, which is only different if the world wraps.
+    ///
+    /// Used to keep things that follow a position in the same place relative to it when it teleports.
+    pub fn nearest(self, position: Vec2, target: Vec2, config: &MapConfig) -> Vec2 {
+        self.wrap_area(config.chunk_tile_count)
+            .map_or(position, |area| {
+                let size = area.size().as_vec2() * config.tile_size;
+                let offset = position - target;
+                target + offset - size * (offset / size).round()
+            })
+    }
+
+    /// Keeps a world position within the bounds of an island.
+    pub fn clamp(self, position: Vec2, tile_size: f32) -> Vec2 {
+        let Self::Island { radius, falloff } = self else {
+            return position;
+        };
+
+        let radius = radius as f32 * tile_size;
+        match falloff {
+            Falloff::Radial => position.clamp_length_max(radius),
+            Falloff::Square => position.clamp(Vec2::splat(-radius), Vec2::splat(radius)),
+        }
+    }
+}
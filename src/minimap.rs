@@ -0,0 +1,176 @@
+//! Minimap HUD panel.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::map::config::MapConfig;
+use crate::map::LoadedTiles;
+use crate::player::Player;
+use crate::{get_single, get_single_mut};
+
+pub fn minimap_plugin(app: &mut App) {
+    app.init_resource::<MinimapConfig>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (update_minimap, rotate_minimap));
+}
+
+/// Size of the minimap panel on screen in pixels.
+const MINIMAP_SIZE: f32 = 200.0;
+/// Width and height of the minimap texture in pixels.
+const MINIMAP_RESOLUTION: u32 = 128;
+/// Colour of areas that are not loaded.
+const UNLOADED_COLOR: [u8; 4] = [0, 0, 0, 255];
+
+#[derive(Resource)]
+pub struct MinimapConfig {
+    /// The amount of tiles each pixel of the minimap covers.
+    pub zoom:        f32,
+    pub orientation: MinimapOrientation,
+}
+
+impl Default for MinimapConfig {
+    fn default() -> Self {
+        Self {
+            zoom:        1.0,
+            orientation: MinimapOrientation::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MinimapOrientation {
+    /// North is always at the top of the minimap.
+    #[default]
+    FixedNorth,
+    /// The minimap rotates so the direction the [`Player`] is facing is at the top.
+    RotateWithPlayer,
+}
+
+/// The image the terrain is drawn onto.
+#[derive(Component)]
+struct MinimapImage(Handle<Image>);
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let image = images.add(Image::new_fill(
+        Extent3d {
+            width:                 MINIMAP_RESOLUTION,
+            height:                MINIMAP_RESOLUTION,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &UNLOADED_COLOR,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ));
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                bottom: Val::Px(10.0),
+                width: Val::Px(MINIMAP_SIZE),
+                height: Val::Px(MINIMAP_SIZE),
+                overflow: Overflow::clip(),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::BLACK.with_a(0.6)),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                ImageBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    image: UiImage::new(image.clone()),
+                    ..default()
+                },
+                MinimapImage(image),
+            ));
+
+            // Player marker, drawn on top of the terrain in the centre of the minimap.
+            parent.spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(6.0),
+                    height: Val::Px(6.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::RED),
+                ..default()
+            });
+        });
+}
+
+/// Redraw the minimap from the loaded chunks around the [`Player`].
+fn update_minimap(
+    mut images: ResMut<Assets<Image>>,
+    mut last_centre: Local<Option<IVec2>>,
+    tiles: LoadedTiles,
+    config: Res<MinimapConfig>,
+    map_config: Res<MapConfig>,
+    player: Query<&Transform, With<Player>>,
+    minimap_image: Query<&MinimapImage>,
+) {
+    let player_transform = get_single!(player);
+    let minimap_image = get_single!(minimap_image);
+
+    let centre = (player_transform.translation.truncate() / map_config.tile_size)
+        .floor()
+        .as_ivec2();
+
+    // Only redraw when something visible on the minimap could have changed.
+    if *last_centre == Some(centre) && !tiles.is_changed() && !config.is_changed() {
+        return;
+    }
+    *last_centre = Some(centre);
+
+    let Some(image) = images.get_mut(&minimap_image.0) else {
+        return;
+    };
+
+    let half_resolution = MINIMAP_RESOLUTION as f32 / 2.0;
+
+    for (index, pixel) in image.data.chunks_exact_mut(4).enumerate() {
+        let column = (index as u32 % MINIMAP_RESOLUTION) as f32;
+        // Image rows go top to bottom, while tile rows go bottom to top.
+        let row = (MINIMAP_RESOLUTION - 1 - index as u32 / MINIMAP_RESOLUTION) as f32;
+
+        let offset = Vec2::new(column - half_resolution, row - half_resolution) * config.zoom;
+        let tile = centre + offset.floor().as_ivec2();
+
+        let color = tiles
+            .get(tile)
+            .map_or(UNLOADED_COLOR, |tile| tile.color.as_rgba_u8());
+        pixel.copy_from_slice(&color);
+    }
+}
+
+/// Rotate the minimap to match its [`MinimapOrientation`].
+fn rotate_minimap(
+    config: Res<MinimapConfig>,
+    player: Query<&Player>,
+    mut minimap_image: Query<&mut Transform, With<MinimapImage>>,
+) {
+    let player = get_single!(player);
+    let mut minimap_transform = get_single_mut!(minimap_image);
+
+    let angle = match config.orientation {
+        MinimapOrientation::FixedNorth => 0.0,
+        MinimapOrientation::RotateWithPlayer => {
+            let facing = player.facing_direction().to_vec2();
+            // UI space has y pointing down, so this turns the facing direction to the top.
+            facing.y.atan2(facing.x) - FRAC_PI_2
+        }
+    };
+
+    minimap_transform.rotation = Quat::from_rotation_z(angle);
+}
@@ -1,59 +1,132 @@
 //! Player movement and animation.
 
+mod animation;
 mod coord_display;
+mod direction;
 mod walk_animation;
 
 use bevy::prelude::*;
 use bevy::sprite::Anchor;
 
+use self::animation::animate_player;
+pub use self::animation::player_clip;
 use self::coord_display::{setup_coords, update_coords};
-use self::walk_animation::{walk_animation, WalkAnimator};
+pub use self::direction::Direction;
+pub use self::walk_animation::walk_animation;
+use self::walk_animation::WalkAnimator;
+use crate::animation::SpriteAnimation;
 use crate::get_single_mut;
+use crate::input::MovementInput;
+use crate::map::config::MapConfig;
+use crate::map::LoadedTiles;
+use crate::world_map::WorldMapState;
 
 pub fn player_plugin(app: &mut App) {
     app.add_systems(Startup, (setup, setup_coords)).add_systems(
         Update,
-        (sprite_movement, walk_animation, update_coords).chain(),
+        (
+            // Stop the player from moving around while looking at the world map.
+            (sprite_movement, walk_animation, animate_player)
+                .chain()
+                .run_if(in_state(WorldMapState::Closed)),
+            update_coords,
+        )
+            .chain(),
     );
 }
 
 const PLAYER_SPEED: f32 = 200.;
 
-#[derive(Component, Default)]
+/// Asset path of the players sprite sheet animations.
+/// If it fails to load, the player tilts `honse.png` instead.
+pub const PLAYER_ANIMATION_PATH: &str = "sprites/player.anim.ron";
+
+/// What the player is doing, which picks the animation clips they play.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum MovementState {
+    #[default]
+    Idle,
+    Walk,
+    /// Moving across a tile the player swims through.
+    Swim,
+}
+
+impl MovementState {
+    /// Name of the state in animation clip names, such as `walk` in `walk_up_left`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Walk => "walk",
+            Self::Swim => "swim",
+        }
+    }
+}
+
+#[derive(Component)]
 pub struct Player {
-    facing_direction: Direction,
+    facing_direction:  Direction,
+    facing_cardinal:   Direction,
+    facing_horizontal: Direction,
+    movement_state:    MovementState,
     /// Stores the state of players [Sprites](Sprite) walk animation.
     /// Should be [None] if the player is not moving
-    walk_animator:    Option<WalkAnimator>,
+    walk_animator:     Option<WalkAnimator>,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            facing_direction:  Direction::Right,
+            facing_cardinal:   Direction::Right,
+            facing_horizontal: Direction::Right,
+            movement_state:    MovementState::Idle,
+            walk_animator:     None,
+        }
+    }
 }
 
 impl Player {
-    /// Starts a timer for walking animation if one doesnt exist already.
-    fn start_walk_animation(&mut self, inverted: bool) {
-        let direction = if inverted {
-            self.facing_direction.next()
-        } else {
-            self.facing_direction
+    /// The direction the player is currently facing, out of all eight.
+    pub const fn facing_direction(&self) -> Direction { self.facing_direction }
+
+    /// The cardinal direction the player is currently facing, for sprites drawn for only four directions.
+    pub const fn facing_cardinal(&self) -> Direction { self.facing_cardinal }
+
+    /// [`Direction::Left`] or [`Direction::Right`], whichever the player last moved towards.
+    ///
+    /// Kept while moving straight up or down, so sideways sprites don't flip back and forth.
+    pub const fn facing_horizontal(&self) -> Direction { self.facing_horizontal }
+
+    /// What the player is doing this frame.
+    pub const fn movement_state(&self) -> MovementState { self.movement_state }
+
+    /// Turns the player to face the way they are moving. Standing still keeps the last facing.
+    pub fn face(&mut self, movement: Vec2) {
+        let (Some(direction), Some(cardinal)) = (
+            Direction::from_vec2(movement),
+            Direction::cardinal_from_vec2(movement),
+        ) else {
+            return;
         };
 
-        if self.walk_animator.is_none() {
-            self.walk_animator = Some(WalkAnimator::new(direction));
+        self.facing_direction = direction;
+        self.facing_cardinal = cardinal;
+        if let Some(horizontal) = direction.horizontal() {
+            self.facing_horizontal = horizontal;
         }
     }
-}
 
-#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
-pub enum Direction {
-    #[default]
-    Right,
-    Left,
-}
+    /// Starts a timer for walking animation if one doesnt exist already.
+    fn start_walk_animation(&mut self) {
+        // Walking towards the screen tilts the other way to walking away from it.
+        let tilt = if self.facing_cardinal == Direction::Down {
+            self.facing_horizontal.opposite()
+        } else {
+            self.facing_horizontal
+        };
 
-impl Direction {
-    const fn next(self) -> Self {
-        match self {
-            Self::Right => Self::Left,
-            Self::Left => Self::Right,
+        if self.walk_animator.is_none() {
+            self.walk_animator = Some(WalkAnimator::new(tilt));
         }
     }
 }
@@ -73,53 +146,49 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         Player::default(),
+        SpriteAnimation::new(asset_server.load(PLAYER_ANIMATION_PATH)),
+        TextureAtlas::default(),
     ));
 }
 
 pub fn sprite_movement(
     time: Res<Time>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut player_query: Query<(&mut Transform, &mut Sprite, &mut Player)>,
-    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+    movement_input: Res<MovementInput>,
+    map_config: Res<MapConfig>,
+    tiles: LoadedTiles,
+    mut player_query: Query<(&mut Transform, &mut Player)>,
 ) {
-    let (mut player_transform, mut sprite, mut player) = get_single_mut!(player_query);
-    let mut camera_transform = get_single_mut!(camera_query);
-
-    let mut direction = Vec3::ZERO;
-
-    for key_press in keyboard_input.get_pressed() {
-        match key_press {
-            KeyCode::ArrowLeft | KeyCode::KeyA => {
-                direction.x += -1.0;
-                player.facing_direction = Direction::Left;
-                player.start_walk_animation(false);
-            }
-            KeyCode::ArrowRight | KeyCode::KeyD => {
-                direction.x += 1.0;
-                player.facing_direction = Direction::Right;
-                player.start_walk_animation(false);
-            }
-            KeyCode::ArrowUp | KeyCode::KeyW => {
-                direction.y += 1.0;
-                player.start_walk_animation(false);
-            }
-            KeyCode::ArrowDown | KeyCode::KeyS => {
-                direction.y += -1.0;
-                player.start_walk_animation(true);
-            }
-            _ => {}
-        }
-    }
+    let (mut player_transform, mut player) = get_single_mut!(player_query);
 
-    if direction.length() > 0.0 {
-        direction = direction.normalize();
-    }
-
-    camera_transform.translation += direction * PLAYER_SPEED * time.delta_seconds();
-    player_transform.translation += direction * PLAYER_SPEED * time.delta_seconds();
+    let direction = movement_input.0;
 
-    match player.facing_direction {
-        Direction::Left => sprite.flip_x = true,
-        Direction::Right => sprite.flip_x = false,
+    if direction != Vec2::ZERO {
+        player.face(direction);
+        player.start_walk_animation();
     }
+
+    let tile_at = |position: Vec3| {
+        (position.truncate() / map_config.tile_size)
+            .floor()
+            .as_ivec2()
+    };
+    let current_tile = tiles.get(tile_at(player_transform.translation));
+    player.movement_state = if direction == Vec2::ZERO {
+        MovementState::Idle
+    } else if current_tile.is_some_and(|tile| tile.swimming) {
+        MovementState::Swim
+    } else {
+        MovementState::Walk
+    };
+    let speed = PLAYER_SPEED * current_tile.map_or(1.0, |tile| tile.speed);
+
+    // Partly tilted gamepad sticks move the player slower.
+    let movement = direction.extend(0.0) * speed * time.delta_seconds();
+
+    // Stay within the edges of the world, or teleport to the other side if it wraps around.
+    let target = (player_transform.translation + movement).truncate();
+    let target = map_config.world_shape.clamp(target, map_config.tile_size);
+    let target = map_config.world_shape.wrap(target, &map_config);
+
+    player_transform.translation = target.extend(player_transform.translation.z);
 }
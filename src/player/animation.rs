@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+
+use super::{Direction, MovementState, Player};
+use crate::animation::{AnimationSheet, SpriteAnimation};
+use crate::get_single_mut;
+
+/// The clip in `sheet` for what the [`Player`] is doing and the way they face, named like `walk_up_left`,
+/// and whether it has to be flipped.
+///
+/// Sheets don't need a clip for every direction. Missing diagonals use the cardinal direction,
+/// then the sideways clip, then the sideways clip for the other side flipped.
+pub fn player_clip(sheet: &AnimationSheet, player: &Player) -> Option<(String, bool)> {
+    let state = player.movement_state().name();
+    let horizontal = player.facing_horizontal();
+
+    [
+        (player.facing_direction(), false),
+        (player.facing_cardinal(), false),
+        (horizontal, false),
+        (horizontal.opposite(), true),
+    ]
+    .into_iter()
+    .map(|(direction, flip)| (format!("{state}_{}", direction.name()), flip))
+    .find(|(clip, _)| sheet.clips.contains_key(clip))
+}
+
+/// Play the clip for what the [`Player`] is doing. Changing direction keeps the clip's timing,
+/// so turning mid stride doesn't restart the walk cycle.
+///
+/// Flips the sprite of players with a [`SpriteAnimation`], while [`walk_animation`](super::walk_animation::walk_animation)
+/// flips the ones without.
+pub fn animate_player(
+    sheets: Res<Assets<AnimationSheet>>,
+    mut last_state: Local<MovementState>,
+    mut player_query: Query<(&Player, &mut SpriteAnimation, &mut Sprite)>,
+) {
+    let (player, mut animation, mut sprite) = get_single_mut!(player_query);
+    let Some((clip, flip)) = sheets
+        .get(&animation.sheet)
+        .and_then(|sheet| player_clip(sheet, player))
+    else {
+        // Until the sheet has loaded the sprite shows its own image, which faces right.
+        sprite.flip_x = player.facing_horizontal() == Direction::Left;
+        return;
+    };
+
+    if *last_state == player.movement_state() {
+        animation.switch(&clip);
+    } else {
+        animation.play(&clip);
+        *last_state = player.movement_state();
+    }
+    sprite.flip_x = flip;
+}
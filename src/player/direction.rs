@@ -0,0 +1,99 @@
+use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
+
+use bevy::prelude::*;
+
+/// One of the eight directions the player can face, going anticlockwise from [`Direction::Right`].
+///
+/// Sprites drawn for only four directions can use [`Direction::cardinal_from_vec2`] instead.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Right,
+    UpRight,
+    Up,
+    UpLeft,
+    Left,
+    DownLeft,
+    Down,
+    DownRight,
+}
+
+impl Direction {
+    pub const ALL: [Self; 8] = [
+        Self::Right,
+        Self::UpRight,
+        Self::Up,
+        Self::UpLeft,
+        Self::Left,
+        Self::DownLeft,
+        Self::Down,
+        Self::DownRight,
+    ];
+
+    /// The closest of the eight directions to `vector`, or [`None`] if it is zero.
+    pub fn from_vec2(vector: Vec2) -> Option<Self> {
+        if vector == Vec2::ZERO {
+            return None;
+        }
+
+        let octant = (vector.y.atan2(vector.x) / FRAC_PI_4).round() as i32;
+        Some(Self::ALL[octant.rem_euclid(8) as usize])
+    }
+
+    /// The closest of the four cardinal directions to `vector`, or [`None`] if it is zero.
+    ///
+    /// Exact diagonals face left or right, as that is how sideways sprites read best.
+    pub fn cardinal_from_vec2(vector: Vec2) -> Option<Self> {
+        if vector == Vec2::ZERO {
+            None
+        } else if vector.x.abs() >= vector.y.abs() {
+            Some(if vector.x < 0.0 {
+                Self::Left
+            } else {
+                Self::Right
+            })
+        } else {
+            Some(if vector.y < 0.0 { Self::Down } else { Self::Up })
+        }
+    }
+
+    /// [`Direction::Left`] or [`Direction::Right`] if this points partly sideways.
+    pub const fn horizontal(self) -> Option<Self> {
+        match self {
+            Self::Right | Self::UpRight | Self::DownRight => Some(Self::Right),
+            Self::Left | Self::UpLeft | Self::DownLeft => Some(Self::Left),
+            Self::Up | Self::Down => None,
+        }
+    }
+
+    /// Name of the direction in animation clip names, such as `up_left` in `walk_up_left`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Right => "right",
+            Self::UpRight => "up_right",
+            Self::Up => "up",
+            Self::UpLeft => "up_left",
+            Self::Left => "left",
+            Self::DownLeft => "down_left",
+            Self::Down => "down",
+            Self::DownRight => "down_right",
+        }
+    }
+
+    /// The direction pointing the other way.
+    pub const fn opposite(self) -> Self { Self::ALL[(self as usize + 4) % 8] }
+
+    /// Unit vector pointing in this direction.
+    pub const fn to_vec2(self) -> Vec2 {
+        match self {
+            Self::Right => Vec2::X,
+            Self::UpRight => Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            Self::Up => Vec2::Y,
+            Self::UpLeft => Vec2::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            Self::Left => Vec2::NEG_X,
+            Self::DownLeft => Vec2::new(-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+            Self::Down => Vec2::NEG_Y,
+            Self::DownRight => Vec2::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        }
+    }
+}
@@ -3,7 +3,9 @@ use std::time::Duration;
 use bevy::prelude::*;
 
 use super::{Direction, Player};
+use crate::animation::SpriteAnimation;
 use crate::get_single_mut;
+use crate::input::MovementInput;
 
 const TILT_SPEED: f32 = 0.25;
 const TILT_RADIUS: f32 = 0.25;
@@ -11,6 +13,7 @@ const TILT_RADIUS: f32 = 0.25;
 #[derive(Component)]
 pub struct WalkAnimator {
     timer:     Timer,
+    /// [`Direction::Left`] or [`Direction::Right`], the way the sprite tilts next.
     direction: Direction,
 }
 
@@ -23,13 +26,18 @@ impl WalkAnimator {
     }
 }
 
-/// If the [`Player`] has a [`WalkAnimator`] active, tilt the [`Sprite`] in the corresponding direction each time the timer runs out.
+/// Flip the [`Player`]s [`Sprite`] to face the way they are facing, and if they have a [`WalkAnimator`] active,
+/// tilt it in the corresponding direction each time the timer runs out.
+///
+/// Players with a [`SpriteAnimation`] walk with that instead.
 pub fn walk_animation(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    movement_input: Res<MovementInput>,
     time: Res<Time>,
-    mut player_query: Query<(&mut Transform, &mut Player)>,
+    mut player_query: Query<(&mut Transform, &mut Sprite, &mut Player), Without<SpriteAnimation>>,
 ) {
-    let (mut player_transform, mut player) = get_single_mut!(player_query);
+    let (mut player_transform, mut sprite, mut player) = get_single_mut!(player_query);
+
+    sprite.flip_x = player.facing_horizontal == Direction::Left;
 
     let Some(tilt) = &mut player.walk_animator else {
         return;
@@ -39,26 +47,18 @@ pub fn walk_animation(
 
     if tilt.timer.finished() {
         let mut transform = Transform::default();
-        match tilt.direction {
-            Direction::Right => transform.rotate_z(TILT_RADIUS),
-            Direction::Left => transform.rotate_z(-TILT_RADIUS),
+        if tilt.direction == Direction::Left {
+            transform.rotate_z(-TILT_RADIUS);
+        } else {
+            transform.rotate_z(TILT_RADIUS);
         }
         player_transform.rotation = transform.rotation;
         // Make sure the timer has a proper timings after running for the first time.
         tilt.timer.set_duration(Duration::from_secs_f32(TILT_SPEED));
-        tilt.direction = tilt.direction.next();
+        tilt.direction = tilt.direction.opposite();
     }
 
-    if !keyboard_input.any_pressed([
-        KeyCode::ArrowRight,
-        KeyCode::KeyD,
-        KeyCode::ArrowLeft,
-        KeyCode::KeyA,
-        KeyCode::ArrowUp,
-        KeyCode::KeyW,
-        KeyCode::ArrowDown,
-        KeyCode::KeyS,
-    ]) {
+    if movement_input.0 == Vec2::ZERO {
         player_transform.rotation = Quat::default();
         player.walk_animator = None;
     }
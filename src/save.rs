@@ -0,0 +1,98 @@
+//! Saving and loading the world.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use serde::{Deserialize, Serialize};
+
+use crate::map::chunk_position::ChunkPosition;
+use crate::map::config::MapConfig;
+use crate::map::world_shape::WorldShape;
+use crate::map::{load_heightmap, load_world_stamps, ChunkReloadEvent, ExploredChunks};
+
+pub fn save_plugin(app: &mut App) {
+    // The explored chunks are recorded with the heightmap and stamps, so those are loaded first.
+    app.add_systems(
+        Startup,
+        load_world.after(load_heightmap).after(load_world_stamps),
+    )
+    .add_systems(
+        Last,
+        (
+            save_world.run_if(on_event::<AppExit>()),
+            save_world.run_if(on_timer(AUTOSAVE_INTERVAL)),
+        ),
+    );
+}
+
+const SAVE_PATH: &str = "saves/world.ron";
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The parts of the world that are stored between sessions.
+#[derive(Serialize, Deserialize)]
+struct WorldSave {
+    seed:             u64,
+    chunk_tile_count: usize,
+    explored:         Vec<ChunkPosition>,
+    #[serde(default)]
+    world_shape:      WorldShape,
+}
+
+fn load_world(
+    mut map_config: ResMut<MapConfig>,
+    mut explored: ResMut<ExploredChunks>,
+    mut ev_chunk_reload: EventWriter<ChunkReloadEvent>,
+) {
+    if !Path::new(SAVE_PATH).exists() {
+        return;
+    }
+
+    let save = match fs::read_to_string(SAVE_PATH)
+        .map_err(|e| e.to_string())
+        .and_then(|save| ron::from_str::<WorldSave>(&save).map_err(|e| e.to_string()))
+    {
+        Ok(save) => save,
+        Err(e) => {
+            error!("Failed to load world from {SAVE_PATH}: {e}");
+            return;
+        }
+    };
+
+    map_config.seed = save.seed;
+    map_config.chunk_tile_count = save.chunk_tile_count;
+    map_config.world_shape = save.world_shape;
+
+    *explored = ExploredChunks::new(&map_config);
+    explored.chunks.extend(save.explored);
+
+    info!("Loaded world from {SAVE_PATH}");
+    ev_chunk_reload.send(ChunkReloadEvent);
+}
+
+fn save_world(explored: Res<ExploredChunks>) {
+    let save = WorldSave {
+        seed:             explored.seed,
+        chunk_tile_count: explored.chunk_tile_count,
+        explored:         explored.chunks.iter().copied().collect(),
+        world_shape:      explored.world_shape,
+    };
+
+    let result = ron::to_string(&save)
+        .map_err(|e| e.to_string())
+        .and_then(|save| {
+            let path = Path::new(SAVE_PATH);
+            path.parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| fs::write(path, save))
+                .map_err(|e| e.to_string())
+        });
+
+    match result {
+        Ok(()) => debug!("Saved world to {SAVE_PATH}"),
+        Err(e) => error!("Failed to save world to {SAVE_PATH}: {e}"),
+    }
+}
@@ -0,0 +1,313 @@
+//! Full screen map of the explored world.
+
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::window::PrimaryWindow;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::input::{Action, UiFocus};
+use crate::map::chunk::Chunk;
+use crate::map::config::MapConfig;
+use crate::map::noise::NoiseMap;
+use crate::map::tile_registry::TileRegistry;
+use crate::map::ExploredChunks;
+use crate::player::Player;
+use crate::{get_single, get_single_mut};
+
+pub fn world_map_plugin(app: &mut App) {
+    app.init_state::<WorldMapState>()
+        .init_resource::<WorldMapView>()
+        .add_systems(Update, toggle_world_map)
+        .add_systems(OnEnter(WorldMapState::Open), open_world_map)
+        .add_systems(OnExit(WorldMapState::Open), close_world_map)
+        .add_systems(
+            Update,
+            (pan_world_map, update_world_map_view)
+                .chain()
+                .run_if(in_state(WorldMapState::Open)),
+        );
+}
+
+/// The largest width or height of the world map image in pixels,
+/// past that tiles get skipped so the image stays a reasonable size.
+const MAX_IMAGE_SIZE: u32 = 4096;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 32.0;
+/// How much one line of mouse wheel scrolling zooms the world map.
+const SCROLL_ZOOM_FACTOR: f32 = 1.1;
+
+#[derive(States, Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WorldMapState {
+    Open,
+    #[default]
+    Closed,
+}
+
+/// Pan and zoom of the world map image.
+#[derive(Resource, Default)]
+struct WorldMapView {
+    /// Position of the top left corner of the image relative to the centre of the screen,
+    /// in screen pixels.
+    offset:     Vec2,
+    /// Screen pixels per image pixel.
+    zoom:       f32,
+    /// Size of the world map image in pixels.
+    image_size: Vec2,
+}
+
+#[derive(Component)]
+struct WorldMapRoot;
+
+#[derive(Component)]
+struct WorldMapImage;
+
+fn toggle_world_map(
+    actions: Res<ButtonInput<Action>>,
+    state: Res<State<WorldMapState>>,
+    mut next_state: ResMut<NextState<WorldMapState>>,
+) {
+    if actions.just_pressed(Action::ToggleWorldMap) {
+        next_state.set(match state.get() {
+            WorldMapState::Open => WorldMapState::Closed,
+            WorldMapState::Closed => WorldMapState::Open,
+        });
+    }
+}
+
+/// Draw every explored chunk into an image and show it over the game.
+///
+/// Chunks are generated again from the [`NoiseMap`], as most explored chunks are no longer loaded.
+#[allow(clippy::too_many_arguments)]
+fn open_world_map(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut view: ResMut<WorldMapView>,
+    explored: Res<ExploredChunks>,
+    config: Res<MapConfig>,
+    noisemap: Res<NoiseMap>,
+    registry: Res<TileRegistry>,
+    player: Query<&Transform, With<Player>>,
+) {
+    let player_transform = get_single!(player);
+
+    let Some((min, max)) = explored_bounds(&explored) else {
+        return;
+    };
+
+    let chunk_tile_count = config.chunk_tile_count as u32;
+    let tile_width = (max.x - min.x + 1) as u32 * chunk_tile_count;
+    let tile_height = (max.y - min.y + 1) as u32 * chunk_tile_count;
+
+    // Amount of tiles along each axis that make up a single pixel.
+    let step = tile_width.max(tile_height).div_ceil(MAX_IMAGE_SIZE);
+    let width = tile_width.div_ceil(step);
+    let height = tile_height.div_ceil(step);
+
+    let image = draw_explored(
+        &explored,
+        &config,
+        &noisemap,
+        &registry,
+        min,
+        step,
+        UVec2::new(width, height),
+    );
+
+    // Centre the view on the player.
+    let player_tile = player_transform.translation.truncate() / config.tile_size;
+    let origin = ((min - IVec2::ONE) * chunk_tile_count as i32).as_vec2();
+    let player_pixel = Vec2::new(
+        (player_tile.x - origin.x) / step as f32,
+        height as f32 - (player_tile.y - origin.y) / step as f32,
+    );
+
+    view.zoom = 1.0;
+    view.offset = -player_pixel;
+    view.image_size = Vec2::new(width as f32, height as f32);
+
+    let image = images.add(image);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::BLACK),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            WorldMapRoot,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    ImageBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            ..default()
+                        },
+                        image: UiImage::new(image),
+                        ..default()
+                    },
+                    WorldMapImage,
+                ))
+                .with_children(|parent| {
+                    // Player marker.
+                    parent.spawn(NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Percent(player_pixel.x / width as f32 * 100.0),
+                            top: Val::Percent(player_pixel.y / height as f32 * 100.0),
+                            width: Val::Px(6.0),
+                            height: Val::Px(6.0),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(Color::RED),
+                        ..default()
+                    });
+                });
+        });
+}
+
+/// Draws every explored chunk into an image of `size`, with a pixel for every `step` tiles.
+///
+/// `min` is the lowest explored chunk position, which goes in the bottom left of the image.
+fn draw_explored(
+    explored: &ExploredChunks,
+    config: &MapConfig,
+    noisemap: &NoiseMap,
+    registry: &TileRegistry,
+    min: IVec2,
+    step: u32,
+    size: UVec2,
+) -> Image {
+    let chunk_tile_count = config.chunk_tile_count as u32;
+
+    let chunks: Vec<_> = explored
+        .chunks
+        .iter()
+        .copied()
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|position| (position, Chunk::new(position, noisemap, config, registry)))
+        .collect();
+
+    // Unexplored areas are left transparent.
+    let mut image = Image::new_fill(
+        Extent3d {
+            width:                 size.x,
+            height:                size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+
+    for (position, chunk) in chunks {
+        let chunk_x = (position.x - min.x) as u32 * chunk_tile_count;
+        let chunk_y = (position.y - min.y) as u32 * chunk_tile_count;
+
+        for (local, tile) in chunk.iter() {
+            let tile_x = chunk_x + local.x;
+            let tile_y = chunk_y + local.y;
+
+            if !tile_x.is_multiple_of(step) || !tile_y.is_multiple_of(step) {
+                continue;
+            }
+
+            // Image rows go top to bottom, while tile rows go bottom to top.
+            let (x, y) = (tile_x / step, size.y - 1 - tile_y / step);
+            let index = ((y * size.x + x) * 4) as usize;
+            image.data[index..index + 4].copy_from_slice(&registry.color(tile).as_rgba_u8());
+        }
+    }
+
+    image
+}
+
+fn close_world_map(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    root: Query<Entity, With<WorldMapRoot>>,
+    image: Query<&UiImage, With<WorldMapImage>>,
+) {
+    if let Ok(image) = image.get_single() {
+        images.remove(&image.texture);
+    }
+
+    for entity in &root {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Pan the world map by dragging with the left mouse button and zoom with the mouse wheel,
+/// unless the mouse is over an egui window.
+fn pan_world_map(
+    mut view: ResMut<WorldMapView>,
+    mut ev_mouse_motion: EventReader<MouseMotion>,
+    mut ev_mouse_wheel: EventReader<MouseWheel>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    focus: Res<UiFocus>,
+) {
+    if focus.pointer {
+        ev_mouse_motion.clear();
+        ev_mouse_wheel.clear();
+        return;
+    }
+
+    let dragged: Vec2 = ev_mouse_motion.read().map(|motion| motion.delta).sum();
+    if mouse_input.pressed(MouseButton::Left) {
+        view.offset += dragged;
+    }
+
+    for wheel in ev_mouse_wheel.read() {
+        let lines = match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / 20.0,
+        };
+
+        // Zoom around the centre of the screen.
+        let zoom = (view.zoom * SCROLL_ZOOM_FACTOR.powf(lines)).clamp(MIN_ZOOM, MAX_ZOOM);
+        let scale = zoom / view.zoom;
+        view.offset *= scale;
+        view.zoom = zoom;
+    }
+}
+
+fn update_world_map_view(
+    view: Res<WorldMapView>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut image: Query<&mut Style, With<WorldMapImage>>,
+) {
+    let window = get_single!(window);
+    let mut style = get_single_mut!(image);
+
+    let top_left = Vec2::new(window.width(), window.height()) / 2.0 + view.offset;
+    let size = view.image_size * view.zoom;
+
+    style.left = Val::Px(top_left.x);
+    style.top = Val::Px(top_left.y);
+    style.width = Val::Px(size.x);
+    style.height = Val::Px(size.y);
+}
+
+/// The lowest and highest explored chunk positions.
+fn explored_bounds(explored: &ExploredChunks) -> Option<(IVec2, IVec2)> {
+    explored.chunks.iter().fold(None, |bounds, position| {
+        let position = IVec2::from(*position);
+        Some(
+            bounds.map_or((position, position), |(min, max): (IVec2, IVec2)| {
+                (min.min(position), max.max(position))
+            }),
+        )
+    })
+}
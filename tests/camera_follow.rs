@@ -0,0 +1,159 @@
+//! Tests for the camera following the player.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_game::camera::{follow_player, CameraFollow};
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::world_shape::WorldShape;
+use bevy_game::player::Player;
+
+const FRAME: Duration = Duration::from_millis(16);
+
+fn app(follow: CameraFollow, config: MapConfig) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .insert_resource(follow)
+        .insert_resource(config)
+        .add_systems(Update, follow_player);
+
+    app.world.spawn((Player::default(), Transform::default()));
+    app.world.spawn((Camera::default(), Transform::default()));
+
+    // The first update has no time passing.
+    app.update();
+    app
+}
+
+fn move_player(app: &mut App, position: Vec2) {
+    app.world
+        .query_filtered::<&mut Transform, With<Player>>()
+        .single_mut(&mut app.world)
+        .translation = position.extend(0.0);
+    app.update();
+}
+
+fn camera(app: &mut App) -> Vec2 {
+    app.world
+        .query_filtered::<&Transform, With<Camera>>()
+        .single(&app.world)
+        .translation
+        .truncate()
+}
+
+/// No smoothing or lookahead, to check each part on its own.
+const RIGID: CameraFollow = CameraFollow {
+    smoothing: 0.0,
+    deadzone:  Vec2::ZERO,
+    lookahead: 0.0,
+};
+
+#[test]
+fn rigid_camera_stays_on_the_player() {
+    let mut app = app(RIGID, MapConfig::default());
+
+    move_player(&mut app, Vec2::new(30.0, -12.0));
+    assert_eq!(camera(&mut app), Vec2::new(30.0, -12.0));
+}
+
+#[test]
+fn deadzone_lets_the_player_move_without_the_camera() {
+    let follow = CameraFollow {
+        deadzone: Vec2::new(50.0, 20.0),
+        ..RIGID
+    };
+    let mut app = app(follow, MapConfig::default());
+
+    move_player(&mut app, Vec2::new(40.0, -15.0));
+    assert_eq!(camera(&mut app), Vec2::ZERO);
+
+    // Leaving the deadzone drags the camera along, keeping the player at its edge.
+    move_player(&mut app, Vec2::new(80.0, -15.0));
+    assert_eq!(camera(&mut app), Vec2::new(30.0, 0.0));
+
+    move_player(&mut app, Vec2::new(80.0, 30.0));
+    assert_eq!(camera(&mut app), Vec2::new(30.0, 10.0));
+}
+
+#[test]
+fn camera_eases_towards_the_player() {
+    let follow = CameraFollow {
+        smoothing: 5.0,
+        ..RIGID
+    };
+    let mut app = app(follow, MapConfig::default());
+
+    move_player(&mut app, Vec2::new(100.0, 0.0));
+    let first = camera(&mut app).x;
+    assert!(first > 0.0 && first < 100.0, "{first}");
+
+    let mut last = first;
+    for _ in 0..200 {
+        app.update();
+        let x = camera(&mut app).x;
+        assert!(x >= last && x <= 100.0);
+        last = x;
+    }
+    assert!((last - 100.0).abs() < 0.01);
+}
+
+#[test]
+fn camera_looks_ahead_of_movement() {
+    let follow = CameraFollow {
+        lookahead: 50.0,
+        ..RIGID
+    };
+    let mut app = app(follow, MapConfig::default());
+
+    move_player(&mut app, Vec2::new(0.0, 10.0));
+    assert_eq!(camera(&mut app), Vec2::new(0.0, 60.0));
+
+    move_player(&mut app, Vec2::new(-5.0, 10.0));
+    assert_eq!(camera(&mut app), Vec2::new(-55.0, 10.0));
+
+    // Standing still looks at the player again.
+    app.update();
+    assert_eq!(camera(&mut app), Vec2::new(-5.0, 10.0));
+}
+
+#[test]
+fn camera_jumps_with_the_player_around_a_wrapped_world() {
+    let config = MapConfig {
+        chunk_tile_count: 10,
+        world_shape: WorldShape::Wrapped {
+            width:  4,
+            height: 4,
+        },
+        ..MapConfig::default()
+    };
+    let width = 40.0 * config.tile_size;
+    let right_edge = 20.0 * config.tile_size;
+    let follow = CameraFollow {
+        smoothing: 5.0,
+        deadzone:  Vec2::splat(10.0),
+        lookahead: 30.0,
+    };
+    let walk_to = |end: Vec2| {
+        let mut app = app(follow, config.clone());
+        for step in 0..20u8 {
+            move_player(
+                &mut app,
+                Vec2::new(right_edge - 20.0 + f32::from(step), 0.0),
+            );
+        }
+        move_player(&mut app, end);
+        camera(&mut app)
+    };
+
+    // Walking off the right edge teleports to the left one,
+    // which should look the same as walking on past the edge.
+    let walked_on = walk_to(Vec2::new(right_edge + 1.0, 0.0));
+    let teleported = walk_to(Vec2::new(right_edge + 1.0 - width, 0.0));
+
+    assert!(
+        (teleported - (walked_on - Vec2::new(width, 0.0))).length() < 0.01,
+        "camera at {teleported} rather than {walked_on} minus the world width"
+    );
+}
@@ -0,0 +1,214 @@
+//! Tests for zooming the camera and panning it in free camera mode.
+
+use std::time::Duration;
+
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::input::{ButtonState, InputPlugin, InputSystem};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{PrimaryWindow, WindowResolution};
+use bevy_game::camera::{camera_plugin, CameraMode, CameraZoom};
+use bevy_game::input::{update_actions, Action, InputBindings, MovementInput, UiFocus};
+use bevy_game::map::config::MapConfig;
+use bevy_game::player::Player;
+use bevy_game::world_map::WorldMapState;
+
+const WINDOW: Vec2 = Vec2::new(800.0, 600.0);
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, InputPlugin, camera_plugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            16,
+        )))
+        .init_resource::<ButtonInput<Action>>()
+        .init_resource::<MovementInput>()
+        .init_resource::<UiFocus>()
+        .init_resource::<InputBindings>()
+        .init_resource::<MapConfig>()
+        .init_state::<WorldMapState>()
+        .add_systems(PreUpdate, update_actions.after(InputSystem));
+
+    app.world.spawn((
+        Window {
+            resolution: WindowResolution::new(WINDOW.x, WINDOW.y),
+            ..default()
+        },
+        PrimaryWindow,
+    ));
+
+    app.update();
+    app
+}
+
+fn camera(app: &mut App) -> (Vec2, f32) {
+    let (transform, projection) = app
+        .world
+        .query_filtered::<(&Transform, &OrthographicProjection), With<Camera>>()
+        .single(&app.world);
+    (transform.translation.truncate(), projection.scale)
+}
+
+/// The world position shown at a position on screen, from the top left like the cursor.
+fn world_at(app: &mut App, screen: Vec2) -> Vec2 {
+    let (position, scale) = camera(app);
+    let offset = Vec2::new(screen.x - WINDOW.x / 2.0, WINDOW.y / 2.0 - screen.y);
+    position + offset * scale
+}
+
+fn set_cursor(app: &mut App, cursor: Vec2) {
+    app.world
+        .query::<&mut Window>()
+        .single_mut(&mut app.world)
+        .set_cursor_position(Some(cursor));
+}
+
+fn scroll(app: &mut App, lines: f32) {
+    app.world.send_event(MouseWheel {
+        unit:   MouseScrollUnit::Line,
+        x:      0.0,
+        y:      lines,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+/// Runs enough frames for the zoom to finish easing.
+fn settle(app: &mut App) {
+    for _ in 0..120 {
+        app.update();
+    }
+}
+
+fn tap_key(app: &mut App, key_code: KeyCode) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        app.world.send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+    }
+}
+
+fn set_middle_button(app: &mut App, state: ButtonState) {
+    app.world.send_event(MouseButtonInput {
+        button: MouseButton::Middle,
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+#[test]
+fn scrolling_zooms_towards_the_cursor() {
+    let mut app = app();
+    tap_key(&mut app, KeyCode::KeyC);
+    let cursor = Vec2::new(650.0, 120.0);
+    set_cursor(&mut app, cursor);
+    let under_cursor = world_at(&mut app, cursor);
+
+    scroll(&mut app, 5.0);
+    app.update();
+
+    // Zooming eases in over several frames.
+    let (_, scale) = camera(&mut app);
+    let target = app.world.resource::<CameraZoom>().target;
+    assert!(target < 1.0);
+    assert!(
+        scale < 1.0 && scale > target,
+        "{scale} should be between 1 and {target}"
+    );
+
+    settle(&mut app);
+    assert!((camera(&mut app).1 - target).abs() < 1e-4);
+    assert!((world_at(&mut app, cursor) - under_cursor).length() < 0.01);
+
+    // And back out again.
+    scroll(&mut app, -10.0);
+    settle(&mut app);
+    assert!(camera(&mut app).1 > 1.0);
+    assert!((world_at(&mut app, cursor) - under_cursor).length() < 0.01);
+}
+
+#[test]
+fn following_camera_zooms_around_the_player() {
+    let mut app = app();
+    app.world.spawn((Player::default(), Transform::default()));
+    app.update();
+
+    // Zooming towards the cursor would move the camera away from the player.
+    set_cursor(&mut app, Vec2::new(650.0, 120.0));
+    scroll(&mut app, 5.0);
+    settle(&mut app);
+
+    let (position, scale) = camera(&mut app);
+    assert!(scale < 1.0);
+    assert_eq!(position, Vec2::ZERO);
+}
+
+#[test]
+fn zoom_stays_within_limits() {
+    let mut app = app();
+    app.world.resource_mut::<CameraZoom>().min = 0.5;
+
+    scroll(&mut app, 100.0);
+    settle(&mut app);
+    assert!((camera(&mut app).1 - 0.5).abs() < 1e-4);
+
+    scroll(&mut app, -1000.0);
+    settle(&mut app);
+    let max = app.world.resource::<CameraZoom>().max;
+    assert!((camera(&mut app).1 - max).abs() < 1e-3);
+}
+
+#[test]
+fn scrolling_over_egui_does_not_zoom() {
+    let mut app = app();
+    app.world.resource_mut::<UiFocus>().pointer = true;
+
+    scroll(&mut app, 5.0);
+    settle(&mut app);
+    assert!((camera(&mut app).1 - 1.0).abs() < f32::EPSILON);
+}
+
+#[test]
+fn free_camera_pans_with_middle_drag() {
+    let mut app = app();
+
+    // Dragging does nothing while following the player.
+    set_middle_button(&mut app, ButtonState::Pressed);
+    app.world.send_event(MouseMotion {
+        delta: Vec2::new(10.0, 0.0),
+    });
+    app.update();
+    assert_eq!(camera(&mut app).0, Vec2::ZERO);
+
+    tap_key(&mut app, KeyCode::KeyC);
+    app.update();
+    assert_eq!(
+        *app.world.resource::<State<CameraMode>>().get(),
+        CameraMode::Free
+    );
+
+    // The world under the cursor moves with it.
+    app.world.send_event(MouseMotion {
+        delta: Vec2::new(10.0, 20.0),
+    });
+    app.update();
+    assert_eq!(camera(&mut app).0, Vec2::new(-10.0, 20.0));
+
+    set_middle_button(&mut app, ButtonState::Released);
+    app.world.send_event(MouseMotion {
+        delta: Vec2::new(10.0, 20.0),
+    });
+    app.update();
+    assert_eq!(camera(&mut app).0, Vec2::new(-10.0, 20.0));
+
+    tap_key(&mut app, KeyCode::KeyC);
+    app.update();
+    assert_eq!(
+        *app.world.resource::<State<CameraMode>>().get(),
+        CameraMode::Follow
+    );
+}
@@ -0,0 +1,153 @@
+//! Chunks have to line up seamlessly with their neighbours at every chunk and tile size.
+
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+
+use bevy::math::{IVec2, UVec2, Vec2};
+use bevy::utils::HashMap;
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::noise::NoiseMap;
+use bevy_game::map::tile_registry::{TileId, TileRegistry};
+use bevy_game::map::world_shape::WorldShape;
+
+/// Every chunk size allowed by the debug menu.
+const CHUNK_TILE_COUNTS: std::ops::RangeInclusive<usize> = 5..=100;
+const TILE_SIZES: [f32; 5] = [1.0, 7.5, 15.0, 33.3, 100.0];
+
+const fn config(seed: u64, chunk_tile_count: usize, tile_size: f32) -> MapConfig {
+    MapConfig {
+        seed,
+        chunk_tile_count,
+        tile_size,
+        pixel_per_tile: false,
+        world_shape: WorldShape::Infinite,
+        heightmap: None,
+        stamps: Vec::new(),
+    }
+}
+
+/// The terrain tiles of a rectangle sampled in one go, as rows going up from the bottom.
+fn sample_area(
+    noisemap: &NoiseMap,
+    registry: &TileRegistry,
+    first_tile: IVec2,
+    size: UVec2,
+) -> Vec<Vec<TileId>> {
+    noisemap
+        .generate_area(first_tile, size)
+        .iter()
+        .map(|row| row.iter().map(|height| registry.terrain(*height)).collect())
+        .collect()
+}
+
+#[test]
+fn chunk_seams_match_single_sample() {
+    let registry = TileRegistry::default();
+
+    for chunk_tile_count in CHUNK_TILE_COUNTS {
+        let config = config(42, chunk_tile_count, 15.0);
+        let noisemap = NoiseMap::new(&config);
+        let size = chunk_tile_count as i32;
+
+        // A 2x2 block of chunks, sampled as a single area.
+        let origin = ChunkPosition { x: -2, y: 1 };
+        let first_tile = origin.first_tile(&config);
+        let sample = sample_area(
+            &noisemap,
+            &registry,
+            first_tile,
+            UVec2::splat(chunk_tile_count as u32 * 2),
+        );
+
+        let mut chunks = HashMap::new();
+        let mut tile_in_chunk = |offset: IVec2| {
+            let (position, local) = ChunkPosition::from_tile(first_tile + offset, &config);
+            assert_eq!(
+                position,
+                ChunkPosition {
+                    x: origin.x + offset.x / size,
+                    y: origin.y + offset.y / size,
+                },
+                "chunk size {chunk_tile_count}, tile {offset} from the first tile"
+            );
+
+            chunks
+                .entry(position)
+                .or_insert_with(|| Chunk::new(position, &noisemap, &config, &registry))
+                .get(local)
+                .unwrap()
+        };
+
+        // Every tile is looked up through the chunk it falls in, so tiles either side of the seams
+        // come from different chunks.
+        for (y, row) in sample.iter().enumerate() {
+            for (x, &expected) in row.iter().enumerate() {
+                let offset = IVec2::new(x as i32, y as i32);
+                assert_eq!(
+                    tile_in_chunk(offset),
+                    expected,
+                    "chunk size {chunk_tile_count}, tile {offset} from the first tile"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn terrain_is_independent_of_chunk_and_tile_size() {
+    let registry = TileRegistry::default();
+    let tiles = [IVec2::ZERO, IVec2::new(-1, -1), IVec2::new(123, -45)];
+    let reference = NoiseMap::new(&config(42, 20, 15.0));
+
+    for chunk_tile_count in CHUNK_TILE_COUNTS {
+        for tile_size in TILE_SIZES {
+            let config = config(42, chunk_tile_count, tile_size);
+            let noisemap = NoiseMap::new(&config);
+
+            for tile in tiles {
+                let (position, local) = ChunkPosition::from_tile(tile, &config);
+                let chunk = Chunk::new(position, &noisemap, &config, &registry);
+
+                assert_eq!(
+                    chunk.get(local),
+                    Some(sample_area(&reference, &registry, tile, UVec2::ONE)[0][0]),
+                    "chunk size {chunk_tile_count}, tile size {tile_size}, tile {tile}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn neighbouring_chunks_touch() {
+    for chunk_tile_count in CHUNK_TILE_COUNTS {
+        for tile_size in TILE_SIZES {
+            let config = config(0, chunk_tile_count, tile_size);
+            let position = ChunkPosition { x: -3, y: 5 };
+
+            let centre = position.centre(&config);
+            let right = ChunkPosition {
+                x: position.x + 1,
+                ..position
+            }
+            .centre(&config);
+            let up = ChunkPosition {
+                y: position.y + 1,
+                ..position
+            }
+            .centre(&config);
+
+            let expected = config.chunk_size();
+            let tolerance = expected * f32::EPSILON * 16.0;
+            assert!(
+                (right - centre - Vec2::new(expected, 0.0)).length() <= tolerance,
+                "chunk size {chunk_tile_count}, tile size {tile_size}"
+            );
+            assert!(
+                (up - centre - Vec2::new(0.0, expected)).length() <= tolerance,
+                "chunk size {chunk_tile_count}, tile size {tile_size}"
+            );
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Tests for the flat tile storage of [`Chunk`].
+
+use bevy::prelude::*;
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::tile_registry::TileId;
+
+const WATER: TileId = TileId(0);
+const DEEP_WATER: TileId = TileId(1);
+const GRASS: TileId = TileId(3);
+const SAND: TileId = TileId(4);
+
+#[test]
+fn tiles_are_indexed_by_local_coordinates() {
+    let mut chunk = Chunk::filled(4, WATER);
+    chunk.set(UVec2::new(1, 3), SAND);
+
+    assert_eq!(chunk.get(UVec2::new(1, 3)), Some(SAND));
+    assert_eq!(chunk.get(UVec2::new(3, 1)), Some(WATER));
+    assert_eq!(chunk.get(UVec2::new(4, 0)), None);
+    assert_eq!(chunk.get(UVec2::new(0, 4)), None);
+
+    // Tiles go row by row from the bottom of the chunk to the top.
+    let top_row: Vec<_> = chunk.iter().skip(12).map(|(_, tile)| tile).collect();
+    assert_eq!(top_row, [WATER, SAND, WATER, WATER]);
+
+    let sand: Vec<_> = chunk
+        .iter()
+        .filter(|&(_, tile)| tile == SAND)
+        .map(|(local, _)| local)
+        .collect();
+    assert_eq!(sand, [UVec2::new(1, 3)]);
+}
+
+#[test]
+fn chunks_round_trip_through_ron() {
+    let mut chunk = Chunk::filled(3, GRASS);
+    chunk.set(UVec2::new(2, 0), DEEP_WATER);
+
+    let saved = ron::to_string(&chunk).unwrap();
+    let loaded: Chunk = ron::from_str(&saved).unwrap();
+
+    assert_eq!(loaded, chunk);
+}
+
+#[test]
+fn invalid_chunks_are_rejected() {
+    assert!(ron::from_str::<Chunk>("(size: 2, tiles: [0, 1, 2])").is_err());
+}
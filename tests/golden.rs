@@ -0,0 +1,190 @@
+//! Snapshot tests comparing chunk textures against reference images in `tests/fixtures/golden`.
+//!
+//! After an intentional change to how chunks look, regenerate the references with
+//! `cargo update-golden` and review the new images before committing them.
+
+use std::path::{Path, PathBuf};
+
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::noise::NoiseMap;
+use bevy_game::map::tile_registry::TileRegistry;
+use bevy_game::map::{ChunkBorderState, ChunkLod};
+use image::RgbaImage;
+
+struct Case {
+    name:             &'static str,
+    seed:             u64,
+    position:         ChunkPosition,
+    chunk_tile_count: usize,
+    tile_size:        f32,
+    pixel_per_tile:   bool,
+    chunk_borders:    ChunkBorderState,
+    lod:              ChunkLod,
+}
+
+const CASES: &[Case] = &[
+    Case {
+        name:             "mixed",
+        seed:             42,
+        position:         ChunkPosition { x: 3, y: 0 },
+        chunk_tile_count: 20,
+        tile_size:        15.0,
+        pixel_per_tile:   false,
+        chunk_borders:    ChunkBorderState::Hidden,
+        lod:              ChunkLod(0),
+    },
+    Case {
+        name:             "mixed_borders",
+        seed:             42,
+        position:         ChunkPosition { x: 3, y: 0 },
+        chunk_tile_count: 20,
+        tile_size:        15.0,
+        pixel_per_tile:   false,
+        chunk_borders:    ChunkBorderState::Shown,
+        lod:              ChunkLod(0),
+    },
+    Case {
+        name:             "uniform",
+        seed:             42,
+        position:         ChunkPosition { x: -7, y: -1 },
+        chunk_tile_count: 20,
+        tile_size:        15.0,
+        pixel_per_tile:   false,
+        chunk_borders:    ChunkBorderState::Hidden,
+        lod:              ChunkLod(0),
+    },
+    Case {
+        name:             "uniform_borders",
+        seed:             42,
+        position:         ChunkPosition { x: -7, y: -1 },
+        chunk_tile_count: 20,
+        tile_size:        15.0,
+        pixel_per_tile:   false,
+        chunk_borders:    ChunkBorderState::Shown,
+        lod:              ChunkLod(0),
+    },
+    Case {
+        name:             "large_chunk_small_tiles",
+        seed:             7,
+        position:         ChunkPosition { x: 0, y: 0 },
+        chunk_tile_count: 64,
+        tile_size:        4.0,
+        pixel_per_tile:   false,
+        chunk_borders:    ChunkBorderState::Hidden,
+        lod:              ChunkLod(0),
+    },
+    Case {
+        name:             "low_detail",
+        seed:             42,
+        position:         ChunkPosition { x: 3, y: 0 },
+        chunk_tile_count: 20,
+        tile_size:        15.0,
+        pixel_per_tile:   false,
+        chunk_borders:    ChunkBorderState::Shown,
+        lod:              ChunkLod(2),
+    },
+    Case {
+        name:             "pixel_per_tile",
+        seed:             42,
+        position:         ChunkPosition { x: 3, y: 0 },
+        chunk_tile_count: 20,
+        tile_size:        15.0,
+        pixel_per_tile:   true,
+        chunk_borders:    ChunkBorderState::Shown,
+        lod:              ChunkLod(0),
+    },
+];
+
+impl Case {
+    fn render(&self) -> RgbaImage {
+        let config = MapConfig {
+            seed: self.seed,
+            chunk_tile_count: self.chunk_tile_count,
+            tile_size: self.tile_size,
+            pixel_per_tile: self.pixel_per_tile,
+            ..MapConfig::default()
+        };
+        let noisemap = NoiseMap::new(&config);
+        let registry = TileRegistry::default();
+
+        Chunk::new(self.position, &noisemap, &config, &registry)
+            .generate_texture_image(&config, &registry, self.chunk_borders, self.lod)
+            .try_into_dynamic()
+            .expect("Chunk textures should convert to an image")
+            .to_rgba8()
+    }
+
+    fn reference_path(&self) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/golden")
+            .join(format!("{}.png", self.name))
+    }
+}
+
+#[test]
+fn chunk_textures_match_references() {
+    let mut failures = Vec::new();
+
+    for case in CASES {
+        let actual = case.render();
+
+        let Ok(reference) = image::open(case.reference_path()) else {
+            failures.push(format!(
+                "{}: missing reference {}",
+                case.name,
+                case.reference_path().display()
+            ));
+            continue;
+        };
+        let reference = reference.to_rgba8();
+
+        if actual.dimensions() != reference.dimensions() {
+            failures.push(format!(
+                "{}: size {:?} doesnt match reference size {:?}",
+                case.name,
+                actual.dimensions(),
+                reference.dimensions()
+            ));
+            continue;
+        }
+
+        let differing = actual
+            .pixels()
+            .zip(reference.pixels())
+            .filter(|(actual, reference)| actual != reference)
+            .count();
+
+        if differing > 0 {
+            let failure_path =
+                Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.png", case.name));
+            actual
+                .save(&failure_path)
+                .expect("Failed to save failed snapshot");
+
+            failures.push(format!(
+                "{}: {differing} pixels differ, actual image saved to {}",
+                case.name,
+                failure_path.display()
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Chunk textures dont match their references, run `cargo update-golden` if this is intended:\n{}",
+        failures.join("\n")
+    );
+}
+
+/// Overwrites the reference images, run through `cargo update-golden`.
+#[test]
+#[ignore = "Only run to update the reference images"]
+fn update_references() {
+    for case in CASES {
+        case.render()
+            .save(case.reference_path())
+            .expect("Failed to save reference image");
+    }
+}
@@ -0,0 +1,91 @@
+//! Tests for using a heightmap image as the terrain.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::heightmap::{Heightmap, HeightmapTerrain, OutsideHeightmap};
+use bevy_game::map::noise::NoiseMap;
+use bevy_game::map::tile_registry::TileRegistry;
+use image::{GrayImage, Luma};
+
+/// A 4x2 image, dark on the left and bright on the right.
+fn image() -> GrayImage { GrayImage::from_fn(4, 2, |x, y| Luma([(x * 80 + y * 10) as u8])) }
+
+fn heightmap() -> Heightmap { Heightmap::from_image(&image()) }
+
+fn config(outside: OutsideHeightmap) -> MapConfig {
+    MapConfig {
+        seed: 42,
+        heightmap: Some(HeightmapTerrain {
+            heightmap: Arc::new(heightmap()),
+            outside,
+        }),
+        ..MapConfig::default()
+    }
+}
+
+#[test]
+fn image_is_centred_with_the_top_row_up() {
+    let heightmap = heightmap();
+
+    // The top left pixel is at the top left of the image, and the image is centred on the origin.
+    assert_eq!(heightmap.get(IVec2::new(-2, 0)), Some(-1.0));
+    assert_eq!(
+        heightmap.get(IVec2::new(1, -1)),
+        Some((250.0_f64 / 255.0).mul_add(2.0, -1.0))
+    );
+    assert_eq!(heightmap.get(IVec2::new(2, 0)), None);
+    assert_eq!(heightmap.get(IVec2::new(0, 1)), None);
+    assert_eq!(heightmap.get(IVec2::new(-3, 0)), None);
+}
+
+#[test]
+fn pixels_become_tiles_through_the_registry() {
+    let registry = TileRegistry::default();
+    let noisemap = NoiseMap::new(&config(OutsideHeightmap::Ocean));
+    let name = |tile| {
+        &registry
+            .get(registry.terrain(noisemap.sample(tile)))
+            .unwrap()
+            .name
+    };
+
+    assert_eq!(name(IVec2::new(-2, 0)), "deep_water");
+    assert_eq!(name(IVec2::new(-1, 0)), "water");
+    assert_eq!(name(IVec2::new(0, 0)), "grass");
+    assert_eq!(name(IVec2::new(1, 0)), "high_grass");
+}
+
+#[test]
+fn outside_the_heightmap_falls_back() {
+    let registry = TileRegistry::default();
+    let procedural = NoiseMap::new(&MapConfig {
+        seed: 42,
+        ..MapConfig::default()
+    });
+    let with_fallback = NoiseMap::new(&config(OutsideHeightmap::Procedural));
+    let ocean = NoiseMap::new(&config(OutsideHeightmap::Ocean));
+
+    for tile in [IVec2::new(10, 0), IVec2::new(-50, 40), IVec2::new(3, -3)] {
+        assert_eq!(
+            with_fallback.sample(tile).to_bits(),
+            procedural.sample(tile).to_bits()
+        );
+        assert_eq!(
+            registry.terrain(ocean.sample(tile)),
+            registry.find("deep_water").unwrap()
+        );
+    }
+}
+
+#[test]
+fn heightmaps_load_from_png() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("heightmap.png");
+    image().save(&path).unwrap();
+
+    assert_eq!(Heightmap::load(&path).unwrap(), heightmap());
+    assert!(Heightmap::load(&path.with_file_name("missing.png")).is_err());
+}
@@ -0,0 +1,336 @@
+//! Tests for reading keyboard and gamepad input through actions and rebinding them.
+
+use std::path::Path;
+
+use bevy::input::gamepad::{
+    GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection, GamepadConnectionEvent,
+    GamepadEvent, GamepadInfo,
+};
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::{ButtonState, InputPlugin, InputSystem};
+use bevy::prelude::*;
+use bevy_game::input::{
+    update_actions, Action, InputBindings, MovementInput, UiFocus, STICK_DEADZONE,
+};
+
+const GAMEPAD: Gamepad = Gamepad { id: 0 };
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, InputPlugin))
+        .init_resource::<ButtonInput<Action>>()
+        .init_resource::<MovementInput>()
+        .init_resource::<UiFocus>()
+        .init_resource::<InputBindings>()
+        .add_systems(PreUpdate, update_actions.after(InputSystem));
+    app
+}
+
+fn press(app: &mut App, key: KeyCode) { send_key(app, key, ButtonState::Pressed); }
+
+fn release(app: &mut App, key: KeyCode) { send_key(app, key, ButtonState::Released); }
+
+/// Sends a key event, the same as a window would.
+fn send_key(app: &mut App, key_code: KeyCode, state: ButtonState) {
+    app.world.send_event(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+fn set_focus(app: &mut App, keyboard: bool) {
+    *app.world.resource_mut::<UiFocus>() = UiFocus {
+        keyboard,
+        pointer: false,
+    };
+}
+
+fn actions(app: &App) -> &ButtonInput<Action> { app.world.resource::<ButtonInput<Action>>() }
+
+fn movement(app: &App) -> Vec2 { app.world.resource::<MovementInput>().0 }
+
+/// Connects a gamepad, the same as a gamepad backend would.
+fn connect_gamepad(app: &mut App) {
+    app.world
+        .send_event(GamepadEvent::from(GamepadConnectionEvent::new(
+            GAMEPAD,
+            GamepadConnection::Connected(GamepadInfo {
+                name: "Test Gamepad".to_string(),
+            }),
+        )));
+    app.update();
+}
+
+fn tilt_stick(app: &mut App, tilt: Vec2) {
+    for (axis, value) in [
+        (GamepadAxisType::LeftStickX, tilt.x),
+        (GamepadAxisType::LeftStickY, tilt.y),
+    ] {
+        app.world
+            .send_event(GamepadEvent::from(GamepadAxisChangedEvent::new(
+                GAMEPAD, axis, value,
+            )));
+    }
+    app.update();
+}
+
+fn set_button(app: &mut App, button: GamepadButtonType, value: f32) {
+    app.world
+        .send_event(GamepadEvent::from(GamepadButtonChangedEvent::new(
+            GAMEPAD, button, value,
+        )));
+    app.update();
+}
+
+#[test]
+fn default_keys_press_actions() {
+    let mut app = app();
+
+    for (key, action) in [
+        (KeyCode::KeyA, Action::MoveLeft),
+        (KeyCode::ArrowLeft, Action::MoveLeft),
+        (KeyCode::KeyD, Action::MoveRight),
+        (KeyCode::KeyW, Action::MoveUp),
+        (KeyCode::ArrowDown, Action::MoveDown),
+        (KeyCode::Equal, Action::ZoomIn),
+        (KeyCode::Minus, Action::ZoomOut),
+        (KeyCode::KeyM, Action::ToggleWorldMap),
+        (KeyCode::F3, Action::ToggleDebug),
+        (KeyCode::KeyC, Action::ToggleFreeCamera),
+        (KeyCode::Escape, Action::Quit),
+    ] {
+        press(&mut app, key);
+        app.update();
+        assert!(
+            actions(&app).pressed(action),
+            "{key:?} should press {action:?}"
+        );
+        assert_eq!(actions(&app).get_pressed().count(), 1);
+
+        release(&mut app, key);
+        app.update();
+        assert!(!actions(&app).pressed(action));
+    }
+}
+
+#[test]
+fn actions_are_just_pressed_once() {
+    let mut app = app();
+
+    press(&mut app, KeyCode::KeyM);
+    app.update();
+    assert!(actions(&app).just_pressed(Action::ToggleWorldMap));
+
+    app.update();
+    assert!(actions(&app).pressed(Action::ToggleWorldMap));
+    assert!(!actions(&app).just_pressed(Action::ToggleWorldMap));
+
+    release(&mut app, KeyCode::KeyM);
+    app.update();
+    assert!(actions(&app).just_released(Action::ToggleWorldMap));
+}
+
+#[test]
+fn either_bound_key_holds_an_action() {
+    let mut app = app();
+
+    press(&mut app, KeyCode::KeyA);
+    press(&mut app, KeyCode::ArrowLeft);
+    app.update();
+    release(&mut app, KeyCode::KeyA);
+    app.update();
+
+    assert!(actions(&app).pressed(Action::MoveLeft));
+    assert!(!actions(&app).just_released(Action::MoveLeft));
+}
+
+#[test]
+fn rebinding_moves_keys_between_actions() {
+    let mut app = app();
+
+    {
+        let mut bindings = app.world.resource_mut::<InputBindings>();
+        bindings.bind(Action::MoveUp, KeyCode::KeyA);
+        bindings.unbind(Action::MoveUp, KeyCode::KeyW);
+        assert_eq!(bindings.keys(Action::MoveLeft), [KeyCode::ArrowLeft]);
+        assert_eq!(
+            bindings.keys(Action::MoveUp),
+            [KeyCode::ArrowUp, KeyCode::KeyA]
+        );
+    }
+
+    press(&mut app, KeyCode::KeyA);
+    press(&mut app, KeyCode::KeyW);
+    app.update();
+
+    assert!(actions(&app).pressed(Action::MoveUp));
+    assert!(!actions(&app).pressed(Action::MoveLeft));
+    assert_eq!(actions(&app).get_pressed().count(), 1);
+}
+
+#[test]
+fn bindings_round_trip_through_a_file() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("bindings/bindings.ron");
+
+    let mut bindings = InputBindings::default();
+    bindings.bind(Action::ZoomIn, KeyCode::PageUp);
+    bindings.unbind(Action::ToggleDebug, KeyCode::F3);
+    bindings.bind_button(Action::MoveUp, GamepadButtonType::South);
+    bindings.save(&path).unwrap();
+
+    assert_eq!(InputBindings::load(&path).unwrap(), bindings);
+}
+
+#[test]
+fn missing_actions_keep_default_keys() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("partial_bindings.ron");
+    std::fs::write(&path, "(keys: {MoveLeft: [KeyJ]})").unwrap();
+
+    let bindings = InputBindings::load(&path).unwrap();
+    assert_eq!(bindings.keys(Action::MoveLeft), [KeyCode::KeyJ]);
+    assert_eq!(
+        bindings.keys(Action::MoveRight),
+        InputBindings::default().keys(Action::MoveRight)
+    );
+    assert_eq!(
+        bindings.buttons(Action::MoveLeft),
+        InputBindings::default().buttons(Action::MoveLeft)
+    );
+}
+
+#[test]
+fn keyboard_movement_is_normalised() {
+    let mut app = app();
+
+    press(&mut app, KeyCode::KeyD);
+    app.update();
+    assert_eq!(movement(&app), Vec2::X);
+
+    press(&mut app, KeyCode::KeyW);
+    app.update();
+    assert!((movement(&app) - Vec2::ONE.normalize()).length() < 1e-6);
+
+    // Opposite directions cancel out.
+    press(&mut app, KeyCode::KeyA);
+    press(&mut app, KeyCode::KeyS);
+    app.update();
+    assert_eq!(movement(&app), Vec2::ZERO);
+}
+
+#[test]
+fn stick_movement_has_a_deadzone() {
+    let mut app = app();
+    connect_gamepad(&mut app);
+
+    tilt_stick(&mut app, Vec2::new(STICK_DEADZONE * 0.9, 0.0));
+    assert_eq!(movement(&app), Vec2::ZERO);
+
+    tilt_stick(&mut app, Vec2::new(0.0, -STICK_DEADZONE * 0.9));
+    assert_eq!(movement(&app), Vec2::ZERO);
+}
+
+#[test]
+fn stick_movement_scales_with_tilt() {
+    let mut app = app();
+    connect_gamepad(&mut app);
+
+    tilt_stick(&mut app, Vec2::new(-0.6, 0.0));
+    let expected = (0.6 - STICK_DEADZONE) / (1.0 - STICK_DEADZONE);
+    assert!((movement(&app) - Vec2::new(-expected, 0.0)).length() < 1e-6);
+
+    tilt_stick(&mut app, Vec2::new(1.0, 0.0));
+    assert!((movement(&app) - Vec2::X).length() < 1e-6);
+
+    // Corners of square stick ranges are no faster than straight lines.
+    tilt_stick(&mut app, Vec2::new(1.0, 1.0));
+    assert!((movement(&app).length() - 1.0).abs() < 1e-6);
+
+    tilt_stick(&mut app, Vec2::ZERO);
+    assert_eq!(movement(&app), Vec2::ZERO);
+}
+
+#[test]
+fn gamepad_buttons_press_actions() {
+    let mut app = app();
+    connect_gamepad(&mut app);
+
+    set_button(&mut app, GamepadButtonType::RightTrigger2, 1.0);
+    assert!(actions(&app).just_pressed(Action::ZoomIn));
+    set_button(&mut app, GamepadButtonType::RightTrigger2, 0.0);
+    assert!(!actions(&app).pressed(Action::ZoomIn));
+
+    set_button(&mut app, GamepadButtonType::LeftTrigger, 1.0);
+    assert!(actions(&app).pressed(Action::ZoomOut));
+    set_button(&mut app, GamepadButtonType::LeftTrigger, 0.0);
+
+    set_button(&mut app, GamepadButtonType::Select, 1.0);
+    assert!(actions(&app).just_pressed(Action::ToggleWorldMap));
+    set_button(&mut app, GamepadButtonType::Select, 0.0);
+
+    set_button(&mut app, GamepadButtonType::DPadLeft, 1.0);
+    assert!(actions(&app).pressed(Action::MoveLeft));
+    assert_eq!(movement(&app), Vec2::NEG_X);
+}
+
+#[test]
+fn gamepad_buttons_can_be_rebound() {
+    let mut app = app();
+    connect_gamepad(&mut app);
+
+    app.world
+        .resource_mut::<InputBindings>()
+        .bind_button(Action::ToggleWorldMap, GamepadButtonType::North);
+
+    set_button(&mut app, GamepadButtonType::North, 1.0);
+    assert!(actions(&app).pressed(Action::ToggleWorldMap));
+
+    app.world
+        .resource_mut::<InputBindings>()
+        .unbind_button(Action::ToggleWorldMap, GamepadButtonType::North);
+    app.update();
+    assert!(!actions(&app).pressed(Action::ToggleWorldMap));
+}
+
+#[test]
+fn ui_keyboard_focus_blocks_actions() {
+    let mut app = app();
+    connect_gamepad(&mut app);
+
+    press(&mut app, KeyCode::KeyD);
+    app.update();
+    assert!(actions(&app).pressed(Action::MoveRight));
+
+    // Typing into a text field.
+    set_focus(&mut app, true);
+    app.update();
+    assert!(actions(&app).just_released(Action::MoveRight));
+    assert_eq!(movement(&app), Vec2::ZERO);
+
+    press(&mut app, KeyCode::KeyM);
+    set_button(&mut app, GamepadButtonType::Select, 1.0);
+    tilt_stick(&mut app, Vec2::X);
+    assert_eq!(actions(&app).get_pressed().count(), 0);
+    assert_eq!(movement(&app), Vec2::ZERO);
+}
+
+#[test]
+fn keys_held_from_a_text_field_need_pressing_again() {
+    let mut app = app();
+
+    // Esc leaves the text field, which shouldn't also quit the game.
+    set_focus(&mut app, true);
+    press(&mut app, KeyCode::Escape);
+    app.update();
+    set_focus(&mut app, false);
+    app.update();
+    app.update();
+    assert!(!actions(&app).pressed(Action::Quit));
+
+    release(&mut app, KeyCode::Escape);
+    app.update();
+    press(&mut app, KeyCode::Escape);
+    app.update();
+    assert!(actions(&app).just_pressed(Action::Quit));
+}
@@ -0,0 +1,227 @@
+//! Runs the map plugin in a headless [`App`] to check which chunks are loaded.
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::world_shape::{Falloff, WorldShape};
+use bevy_game::map::{map_plugin, ChunkLod, ChunkReloadEvent, ExploredChunks, Map};
+
+/// Size of the fake window the camera renders to.
+const VIEWPORT: Vec2 = Vec2::new(1280.0, 720.0);
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), map_plugin))
+        .init_asset::<Image>();
+
+    app.world.spawn((
+        Camera2dBundle::default().camera,
+        Transform::default(),
+        projection(1.0),
+    ));
+
+    app.update();
+    app
+}
+
+/// An [`OrthographicProjection`] with the area a window of [`VIEWPORT`] would give it.
+fn projection(scale: f32) -> OrthographicProjection {
+    let half_size = VIEWPORT / 2.0 * scale;
+
+    OrthographicProjection {
+        scale,
+        area: Rect::from_corners(-half_size, half_size),
+        ..default()
+    }
+}
+
+fn move_camera(app: &mut App, translation: Vec2) {
+    let mut transform = app
+        .world
+        .query_filtered::<&mut Transform, With<Camera>>()
+        .single_mut(&mut app.world);
+    transform.translation = translation.extend(0.0);
+}
+
+fn zoom_camera(app: &mut App, scale: f32) {
+    *app.world
+        .query::<&mut OrthographicProjection>()
+        .single_mut(&mut app.world) = projection(scale);
+}
+
+fn loaded_chunks(app: &App) -> HashSet<ChunkPosition> {
+    app.world.resource::<Map>().keys().copied().collect()
+}
+
+/// Every chunk that is at least partly on screen.
+fn visible_chunks(app: &mut App) -> HashSet<ChunkPosition> {
+    let (camera, projection) = app
+        .world
+        .query_filtered::<(&Transform, &OrthographicProjection), With<Camera>>()
+        .single(&app.world);
+    let config = app.world.resource::<MapConfig>();
+
+    let centre = camera.translation.truncate();
+    let min = centre + projection.area.min;
+    let max = centre + projection.area.max;
+
+    let min_tile = (min / config.tile_size).floor().as_ivec2();
+    let max_tile = (max / config.tile_size).floor().as_ivec2();
+    let min_chunk = ChunkPosition::from_tile(min_tile, config).0;
+    let max_chunk = ChunkPosition::from_tile(max_tile, config).0;
+
+    (min_chunk.x..=max_chunk.x)
+        .flat_map(|x| (min_chunk.y..=max_chunk.y).map(move |y| ChunkPosition { x, y }))
+        .collect()
+}
+
+/// Checks every visible chunk is loaded, without loading chunks far off screen,
+/// and that the [`Map`] matches the spawned chunk entities.
+fn assert_chunks_fit_view(app: &mut App) {
+    let loaded = loaded_chunks(app);
+    let visible = visible_chunks(app);
+
+    let missing: Vec<_> = visible.difference(&loaded).collect();
+    assert!(missing.is_empty(), "Visible chunks not loaded: {missing:?}");
+
+    // Some chunks just off screen get loaded ahead of time, but no further.
+    let margin = 3;
+    let (min_x, max_x) = (
+        visible.iter().map(|p| p.x).min().unwrap() - margin,
+        visible.iter().map(|p| p.x).max().unwrap() + margin,
+    );
+    let (min_y, max_y) = (
+        visible.iter().map(|p| p.y).min().unwrap() - margin,
+        visible.iter().map(|p| p.y).max().unwrap() + margin,
+    );
+    let far: Vec<_> = loaded
+        .iter()
+        .filter(|p| p.x < min_x || p.x > max_x || p.y < min_y || p.y > max_y)
+        .collect();
+    assert!(far.is_empty(), "Chunks far off screen are loaded: {far:?}");
+
+    let spawned: HashSet<_> = app
+        .world
+        .query_filtered::<&ChunkPosition, With<Chunk>>()
+        .iter(&app.world)
+        .copied()
+        .collect();
+    assert_eq!(spawned, loaded, "Spawned chunk entities dont match the map");
+}
+
+#[test]
+fn loads_chunks_around_camera() {
+    let mut app = app();
+
+    assert!(!loaded_chunks(&app).is_empty());
+    assert_chunks_fit_view(&mut app);
+}
+
+#[test]
+fn moving_loads_and_unloads_chunks() {
+    let mut app = app();
+    let before = loaded_chunks(&app);
+
+    let chunk_size = app.world.resource::<MapConfig>().chunk_size();
+    move_camera(&mut app, Vec2::new(chunk_size * 20.0, -chunk_size * 15.0));
+    app.update();
+
+    let after = loaded_chunks(&app);
+    assert!(
+        before.is_disjoint(&after),
+        "Chunks from the old view are still loaded"
+    );
+    assert_chunks_fit_view(&mut app);
+
+    // Moving back loads the original chunks again.
+    move_camera(&mut app, Vec2::ZERO);
+    app.update();
+
+    assert_eq!(loaded_chunks(&app), before);
+}
+
+#[test]
+fn zooming_out_loads_more_chunks() {
+    let mut app = app();
+    let before = loaded_chunks(&app);
+
+    zoom_camera(&mut app, 4.0);
+    app.update();
+
+    let after = loaded_chunks(&app);
+    assert!(after.len() > before.len());
+    assert!(after.is_superset(&before));
+    assert_eq!(*app.world.resource::<ChunkLod>(), ChunkLod::from_scale(4.0));
+    assert_chunks_fit_view(&mut app);
+
+    zoom_camera(&mut app, 0.5);
+    app.update();
+
+    assert!(loaded_chunks(&app).len() < before.len());
+    assert_eq!(*app.world.resource::<ChunkLod>(), ChunkLod(0));
+    assert_chunks_fit_view(&mut app);
+}
+
+#[test]
+fn reload_event_respawns_chunks() {
+    let mut app = app();
+    let before = loaded_chunks(&app);
+    let entities: HashSet<Entity> = app.world.resource::<Map>().values().copied().collect();
+
+    app.world.resource_mut::<MapConfig>().seed = 42;
+    app.world.send_event(ChunkReloadEvent);
+    app.update();
+
+    assert_eq!(loaded_chunks(&app), before);
+
+    let new_entities: HashSet<Entity> = app.world.resource::<Map>().values().copied().collect();
+    assert!(
+        entities.is_disjoint(&new_entities),
+        "Chunks werent respawned"
+    );
+    for entity in entities {
+        assert!(
+            app.world.get_entity(entity).is_none(),
+            "Old chunk {entity:?} wasnt despawned"
+        );
+    }
+    assert_chunks_fit_view(&mut app);
+}
+
+#[test]
+fn changing_chunk_size_reloads_chunks() {
+    let mut app = app();
+
+    app.world.resource_mut::<MapConfig>().chunk_tile_count = 50;
+    app.world.send_event(ChunkReloadEvent);
+    app.update();
+
+    let mut chunks = app.world.query::<&Chunk>();
+    assert!(chunks.iter(&app.world).all(|chunk| chunk.size() == 50));
+    assert_chunks_fit_view(&mut app);
+}
+
+#[test]
+fn changing_the_terrain_forgets_explored_chunks() {
+    let mut app = app();
+    let explored = |app: &App| app.world.resource::<ExploredChunks>().chunks.clone();
+    let before = explored(&app);
+    assert_eq!(before, loaded_chunks(&app));
+
+    // Drawing the chunks bigger doesn't change the terrain.
+    app.world.resource_mut::<MapConfig>().tile_size = 30.0;
+    app.world.send_event(ChunkReloadEvent);
+    app.update();
+    assert!(explored(&app).is_superset(&before));
+
+    app.world.resource_mut::<MapConfig>().world_shape = WorldShape::Island {
+        radius:  200,
+        falloff: Falloff::Radial,
+    };
+    app.world.send_event(ChunkReloadEvent);
+    app.update();
+    assert_eq!(explored(&app), loaded_chunks(&app));
+    assert!(!explored(&app).is_superset(&before));
+}
@@ -0,0 +1,133 @@
+//! Tests for snapping the camera to whole pixels and texels.
+
+use std::time::Duration;
+
+use bevy::input::mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{PrimaryWindow, WindowResolution};
+use bevy_game::camera::{
+    camera_plugin, pixel_perfect_scale, texel_size, CameraMode, CameraZoom, PixelPerfect,
+};
+use bevy_game::input::{Action, UiFocus};
+use bevy_game::map::config::MapConfig;
+use bevy_game::world_map::WorldMapState;
+
+fn app(window_size: Vec2) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TransformPlugin, InputPlugin, camera_plugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            16,
+        )))
+        .insert_resource(PixelPerfect(true))
+        .init_resource::<ButtonInput<Action>>()
+        .init_resource::<UiFocus>()
+        .init_resource::<MapConfig>()
+        .init_state::<WorldMapState>();
+
+    app.world.spawn((
+        Window {
+            resolution: WindowResolution::new(window_size.x, window_size.y),
+            ..default()
+        },
+        PrimaryWindow,
+    ));
+
+    app.update();
+    app
+}
+
+/// Where the camera is drawn from, and its scale.
+fn camera(app: &mut App) -> (Vec2, f32) {
+    let (transform, projection) = app
+        .world
+        .query_filtered::<(&GlobalTransform, &OrthographicProjection), With<Camera>>()
+        .single(&app.world);
+    (transform.translation().truncate(), projection.scale)
+}
+
+fn is_whole(value: f32) -> bool { (value - value.round()).abs() < 1e-3 }
+
+#[test]
+fn scales_are_whole_pixels_per_texel() {
+    for texel in [1.0, 3.0, 15.0] {
+        for scale in [0.07, 0.3, 0.9, 1.0, 1.4, 2.6, 7.0] {
+            let snapped = pixel_perfect_scale(scale, texel);
+            let pixels_per_texel = texel / snapped;
+            assert!(
+                is_whole(pixels_per_texel) || is_whole(pixels_per_texel.recip()),
+                "{scale} snapped to {snapped} with texels of {texel}"
+            );
+            // Never more than half way to the next ratio.
+            assert!(snapped / scale < 1.5 && scale / snapped < 1.5);
+        }
+    }
+}
+
+#[test]
+fn zoom_settles_on_whole_pixels_per_texel() {
+    let mut app = app(Vec2::new(800.0, 600.0));
+    let config = app.world.resource::<MapConfig>().clone();
+
+    for lines in [3.0, 4.0, -20.0] {
+        app.world.send_event(MouseWheel {
+            unit:   MouseScrollUnit::Line,
+            x:      0.0,
+            y:      lines,
+            window: Entity::PLACEHOLDER,
+        });
+        for _ in 0..120 {
+            app.update();
+        }
+
+        let (_, scale) = camera(&mut app);
+        let target = app.world.resource::<CameraZoom>().target;
+        let pixels_per_texel = texel_size(scale, &config) / scale;
+        assert!(
+            is_whole(pixels_per_texel) || is_whole(pixels_per_texel.recip()),
+            "zoom target {target} settled on {scale}"
+        );
+    }
+}
+
+#[test]
+fn camera_is_drawn_on_whole_pixels_but_moves_smoothly() {
+    for window_size in [Vec2::new(800.0, 600.0), Vec2::new(801.0, 599.0)] {
+        let mut app = app(window_size);
+        app.world.resource_mut::<CameraZoom>().target = 0.25;
+        for _ in 0..120 {
+            app.update();
+        }
+        app.insert_state(CameraMode::Free);
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Middle,
+            state:  ButtonState::Pressed,
+            window: Entity::PLACEHOLDER,
+        });
+
+        // Dragging less than a pixel each frame still adds up.
+        for frame in 1..=10u8 {
+            app.world.send_event(MouseMotion {
+                delta: Vec2::new(-0.3, 0.1),
+            });
+            app.update();
+
+            let (drawn, scale) = camera(&mut app);
+            assert!((scale - 0.25).abs() < f32::EPSILON);
+            let expected = Vec2::new(0.3, 0.1) * 0.25 * f32::from(frame);
+
+            // Drawn at the centre of a pixel when the window is an odd size across.
+            let offset = (window_size % 2.0) / 2.0;
+            let pixels = drawn / 0.25 - offset;
+            assert!(
+                is_whole(pixels.x) && is_whole(pixels.y),
+                "drawn at {drawn} for a {window_size} window"
+            );
+            assert!(
+                (drawn - expected).abs().max_element() <= 0.125 + 1e-4,
+                "drawn at {drawn} instead of {expected} at frame {frame}"
+            );
+        }
+    }
+}
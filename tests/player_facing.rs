@@ -0,0 +1,137 @@
+//! Tests for which way the player faces while moving.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_game::input::MovementInput;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::tile_registry::TileRegistry;
+use bevy_game::map::Map;
+use bevy_game::player::{sprite_movement, walk_animation, Direction, Player};
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            16,
+        )))
+        .init_resource::<MovementInput>()
+        .init_resource::<MapConfig>()
+        .init_resource::<Map>()
+        .insert_resource(TileRegistry::default())
+        .add_systems(Update, (sprite_movement, walk_animation).chain());
+
+    app.world
+        .spawn((Player::default(), Sprite::default(), Transform::default()));
+    app.update();
+    app
+}
+
+fn walk(app: &mut App, movement: Vec2) -> (Direction, Direction, bool) {
+    app.world.resource_mut::<MovementInput>().0 = movement;
+    app.update();
+
+    let (player, sprite) = app.world.query::<(&Player, &Sprite)>().single(&app.world);
+    (
+        player.facing_direction(),
+        player.facing_cardinal(),
+        sprite.flip_x,
+    )
+}
+
+#[test]
+fn vectors_pick_the_closest_direction() {
+    for direction in Direction::ALL {
+        assert_eq!(Direction::from_vec2(direction.to_vec2()), Some(direction));
+        assert_eq!(
+            Direction::from_vec2(direction.to_vec2() * 0.1),
+            Some(direction)
+        );
+        assert_eq!(
+            Direction::from_vec2(-direction.to_vec2()),
+            Some(direction.opposite())
+        );
+    }
+    for direction in [
+        Direction::Right,
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+    ] {
+        assert_eq!(
+            Direction::cardinal_from_vec2(direction.to_vec2()),
+            Some(direction)
+        );
+    }
+
+    assert_eq!(
+        Direction::from_vec2(Vec2::new(1.0, 0.3)),
+        Some(Direction::Right)
+    );
+    assert_eq!(
+        Direction::from_vec2(Vec2::new(1.0, 0.6)),
+        Some(Direction::UpRight)
+    );
+    assert_eq!(
+        Direction::from_vec2(Vec2::new(-0.2, -1.0)),
+        Some(Direction::Down)
+    );
+    assert_eq!(Direction::from_vec2(Vec2::ZERO), None);
+
+    // Diagonals face sideways with only four directions.
+    assert_eq!(
+        Direction::cardinal_from_vec2(Vec2::new(-1.0, -1.0)),
+        Some(Direction::Left)
+    );
+    assert_eq!(
+        Direction::cardinal_from_vec2(Vec2::new(0.9, -1.0)),
+        Some(Direction::Down)
+    );
+    assert_eq!(Direction::cardinal_from_vec2(Vec2::ZERO), None);
+}
+
+#[test]
+fn player_faces_the_way_they_walk() {
+    let mut app = app();
+
+    assert_eq!(
+        walk(&mut app, Vec2::new(-1.0, 1.0).normalize()),
+        (Direction::UpLeft, Direction::Left, true)
+    );
+    assert_eq!(
+        walk(&mut app, Vec2::new(0.2, -1.0).normalize()),
+        (Direction::Down, Direction::Down, true)
+    );
+    assert_eq!(
+        walk(&mut app, Vec2::new(0.5, -0.7)),
+        (Direction::DownRight, Direction::Down, false)
+    );
+    assert_eq!(
+        walk(&mut app, Vec2::X),
+        (Direction::Right, Direction::Right, false)
+    );
+}
+
+#[test]
+fn walking_straight_up_or_down_keeps_the_sprite_flip() {
+    let mut app = app();
+
+    walk(&mut app, Vec2::NEG_X);
+    assert_eq!(
+        walk(&mut app, Vec2::Y),
+        (Direction::Up, Direction::Up, true)
+    );
+    assert_eq!(
+        walk(&mut app, Vec2::NEG_Y),
+        (Direction::Down, Direction::Down, true)
+    );
+
+    // Standing still keeps facing the same way.
+    assert_eq!(
+        walk(&mut app, Vec2::ZERO),
+        (Direction::Down, Direction::Down, true)
+    );
+    let player = app.world.query::<&Player>().single(&app.world);
+    assert_eq!(player.facing_horizontal(), Direction::Left);
+}
@@ -0,0 +1,358 @@
+//! Tests for playing sprite sheet animations and picking the players clips.
+
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::HashMap;
+use bevy_game::animation::{
+    animate_sprites, animation_plugin, AnimationSheet, AnimationSheetData, LoopMode,
+    SpriteAnimation, SpriteClip,
+};
+use bevy_game::input::MovementInput;
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::tile_registry::TileRegistry;
+use bevy_game::map::Map;
+use bevy_game::player::{
+    player_clip, sprite_movement, MovementState, Player, PLAYER_ANIMATION_PATH,
+};
+
+const FIXTURES: &str = "tests/fixtures/animation";
+
+fn clip(frames: &[usize], mode: LoopMode) -> SpriteClip {
+    SpriteClip {
+        frames: frames.to_vec(),
+        fps: 10.0,
+        mode,
+    }
+}
+
+fn sheet(clips: &[(&str, SpriteClip)]) -> AnimationSheet {
+    AnimationSheet {
+        texture: Handle::default(),
+        layout:  Handle::weak_from_u128(1),
+        clips:   clips
+            .iter()
+            .map(|(name, clip)| ((*name).to_string(), clip.clone()))
+            .collect::<HashMap<_, _>>(),
+    }
+}
+
+#[test]
+fn clips_loop_stop_or_bounce() {
+    let frames_at = |mode| {
+        let clip = clip(&[3, 4, 5], mode);
+        (0..8u8)
+            .map(|step| clip.frames[clip.frame_at(f32::from(step) / 10.0 + 0.05)])
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(frames_at(LoopMode::Loop), [3, 4, 5, 3, 4, 5, 3, 4]);
+    assert_eq!(frames_at(LoopMode::Once), [3, 4, 5, 5, 5, 5, 5, 5]);
+    assert_eq!(frames_at(LoopMode::PingPong), [3, 4, 5, 4, 3, 4, 5, 4]);
+
+    assert_eq!(clip(&[7], LoopMode::PingPong).frame_at(3.0), 0);
+    assert!(
+        (clip(&[3, 4, 5], LoopMode::PingPong)
+            .cycle_duration()
+            .unwrap()
+            - 0.4)
+            .abs()
+            < 1e-6
+    );
+    assert_eq!(clip(&[3, 4, 5], LoopMode::Once).cycle_duration(), None);
+}
+
+#[test]
+fn invalid_sheets_are_rejected() {
+    let sheet_with = |clip: &str| {
+        format!(
+            r#"(texture: "a.png", frame_size: (8, 8), columns: 2, rows: 2, clips: {{ "walk_right": {clip} }})"#
+        )
+    };
+
+    assert!(AnimationSheetData::from_ron(&sheet_with("(frames: [0, 3], fps: 5.0)")).is_ok());
+    for clip in [
+        "(frames: [], fps: 5.0)",
+        "(frames: [4], fps: 5.0)",
+        "(frames: [0], fps: 0.0)",
+        "(frames: [0], fps: 5.0, mode: Sideways)",
+    ] {
+        assert!(
+            AnimationSheetData::from_ron(&sheet_with(clip)).is_err(),
+            "{clip} should be rejected"
+        );
+    }
+}
+
+/// Loads an `.anim.ron` file from `assets`, waiting until it has loaded.
+fn load_sheet(assets: &str, path: &'static str) -> (App, Handle<AnimationSheet>) {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin {
+            file_path: assets.to_string(),
+            ..default()
+        },
+    ))
+    .init_asset::<Image>()
+    .init_asset::<TextureAtlasLayout>()
+    .add_plugins(animation_plugin);
+
+    let handle: Handle<AnimationSheet> = app.world.resource::<AssetServer>().load(path);
+    for _ in 0..1000 {
+        if app
+            .world
+            .resource::<Assets<AnimationSheet>>()
+            .contains(&handle)
+        {
+            break;
+        }
+        app.update();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    (app, handle)
+}
+
+#[test]
+fn sheets_load_as_assets() {
+    let (app, handle) = load_sheet(FIXTURES, "walker.anim.ron");
+
+    let sheets = app.world.resource::<Assets<AnimationSheet>>();
+    let sheet = sheets.get(&handle).expect("the sheet should load");
+    assert_eq!(sheet.clips.len(), 4);
+    assert_eq!(sheet.clips["swim_right"].mode, LoopMode::PingPong);
+    assert_eq!(
+        sheet.texture.path().map(ToString::to_string).as_deref(),
+        Some("walker.png")
+    );
+
+    let layout = app
+        .world
+        .resource::<Assets<TextureAtlasLayout>>()
+        .get(&sheet.layout)
+        .unwrap();
+    assert_eq!(layout.len(), 8);
+    assert_eq!(layout.textures[5], Rect::new(16.0, 16.0, 32.0, 32.0));
+}
+
+#[test]
+fn the_player_sheet_ships_with_the_game() {
+    let (app, handle) = load_sheet("assets", PLAYER_ANIMATION_PATH);
+
+    let sheets = app.world.resource::<Assets<AnimationSheet>>();
+    let sheet = sheets.get(&handle).expect("the player sheet should load");
+    for state in [
+        MovementState::Idle,
+        MovementState::Walk,
+        MovementState::Swim,
+    ] {
+        let clip = format!("{}_right", state.name());
+        assert!(sheet.clips.contains_key(&clip), "missing {clip}");
+    }
+
+    // The frames have to cover the whole image.
+    let layout = app
+        .world
+        .resource::<Assets<TextureAtlasLayout>>()
+        .get(&sheet.layout)
+        .unwrap();
+    let image = Path::new("assets").join(sheet.texture.path().unwrap().path());
+    let (width, height) = image::image_dimensions(image).unwrap();
+    assert_eq!(layout.size, UVec2::new(width, height).as_vec2());
+}
+
+#[test]
+fn animations_show_the_frame_their_clip_is_on() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            50,
+        )))
+        .init_asset::<AnimationSheet>()
+        .add_systems(Update, animate_sprites);
+
+    let handle = app
+        .world
+        .resource_mut::<Assets<AnimationSheet>>()
+        .add(sheet(&[
+            ("walk_right", clip(&[4, 5, 6, 7], LoopMode::Loop)),
+            ("walk_up", clip(&[0, 1, 2, 3], LoopMode::Loop)),
+            ("idle_right", clip(&[8, 9], LoopMode::Once)),
+        ]));
+    let mut animation = SpriteAnimation::new(handle);
+    animation.play("walk_right");
+    let entity = app
+        .world
+        .spawn((
+            animation,
+            TextureAtlas::default(),
+            Handle::<Image>::default(),
+        ))
+        .id();
+
+    // The first update has no time passing.
+    app.update();
+    assert_eq!(
+        app.world.get::<TextureAtlas>(entity).unwrap().layout,
+        Handle::weak_from_u128(1)
+    );
+
+    let mut frames = Vec::new();
+    let step = |app: &mut App, change: &dyn Fn(&mut SpriteAnimation)| {
+        change(&mut app.world.get_mut::<SpriteAnimation>(entity).unwrap());
+        app.update();
+        app.world.get::<TextureAtlas>(entity).unwrap().index
+    };
+    for _ in 0..4 {
+        frames.push(step(&mut app, &|_| {}));
+    }
+    assert_eq!(frames, [4, 5, 5, 6]);
+
+    // Turning carries on the same stride, while changing what is being done starts over.
+    assert_eq!(step(&mut app, &|animation| animation.switch("walk_up")), 2);
+    assert_eq!(step(&mut app, &|animation| animation.play("walk_up")), 3);
+    assert_eq!(step(&mut app, &|animation| animation.play("idle_right")), 8);
+    for _ in 0..10 {
+        step(&mut app, &|_| {});
+    }
+    assert_eq!(step(&mut app, &|_| {}), 9);
+}
+
+#[test]
+fn sprites_keep_their_image_when_the_sheet_fails_to_load() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin {
+            file_path: FIXTURES.to_string(),
+            ..default()
+        },
+    ))
+    .init_asset::<Image>()
+    .init_asset::<TextureAtlasLayout>()
+    .add_plugins(animation_plugin);
+
+    let image = Handle::<Image>::weak_from_u128(2);
+    let sheet = app.world.resource::<AssetServer>().load("missing.anim.ron");
+    let entity = app
+        .world
+        .spawn((
+            SpriteAnimation::new(sheet),
+            TextureAtlas::default(),
+            image.clone(),
+        ))
+        .id();
+
+    for _ in 0..1000 {
+        // Drawn with its own image while the sheet loads.
+        assert_eq!(app.world.get::<Handle<Image>>(entity), Some(&image));
+        if app.world.get::<SpriteAnimation>(entity).is_none() {
+            break;
+        }
+        app.update();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert!(app.world.get::<SpriteAnimation>(entity).is_none());
+    assert!(app.world.get::<TextureAtlas>(entity).is_none());
+    assert_eq!(app.world.get::<Handle<Image>>(entity), Some(&image));
+}
+
+fn player_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            16,
+        )))
+        .init_resource::<MovementInput>()
+        .init_resource::<MapConfig>()
+        .init_resource::<Map>()
+        .insert_resource(TileRegistry::default())
+        .add_systems(Update, sprite_movement);
+
+    app.world
+        .spawn((Player::default(), Sprite::default(), Transform::default()));
+    app.update();
+    app
+}
+
+fn clip_for(app: &mut App, sheet: &AnimationSheet, movement: Vec2) -> Option<(String, bool)> {
+    app.world.resource_mut::<MovementInput>().0 = movement;
+    app.update();
+
+    let player = app.world.query::<&Player>().single(&app.world);
+    player_clip(sheet, player)
+}
+
+#[test]
+fn player_clips_fall_back_to_fewer_directions() {
+    let mut app = player_app();
+    let walk = || clip(&[0], LoopMode::Loop);
+
+    let eight_way = sheet(&[
+        ("idle_right", walk()),
+        ("walk_up_left", walk()),
+        ("walk_left", walk()),
+        ("walk_right", walk()),
+    ]);
+    assert_eq!(
+        clip_for(&mut app, &eight_way, Vec2::new(-1.0, 1.0)),
+        Some(("walk_up_left".to_string(), false))
+    );
+    let player = app.world.query::<&Player>().single(&app.world);
+    assert_eq!(player.movement_state(), MovementState::Walk);
+
+    // No down clips, so the sideways one the player last faced is used.
+    assert_eq!(
+        clip_for(&mut app, &eight_way, Vec2::NEG_Y),
+        Some(("walk_left".to_string(), false))
+    );
+
+    // Only drawn facing right, so left is flipped.
+    let sideways = sheet(&[("idle_right", walk()), ("walk_right", walk())]);
+    assert_eq!(
+        clip_for(&mut app, &sideways, Vec2::new(-1.0, -0.2)),
+        Some(("walk_right".to_string(), true))
+    );
+    assert_eq!(
+        clip_for(&mut app, &sideways, Vec2::ZERO),
+        Some(("idle_right".to_string(), true))
+    );
+    assert_eq!(clip_for(&mut app, &sheet(&[]), Vec2::X), None);
+}
+
+#[test]
+fn players_swim_in_water() {
+    let mut app = player_app();
+    let config = app.world.resource::<MapConfig>().clone();
+    let registry = TileRegistry::default();
+
+    // The player stands on grass, with water all around.
+    let (position, local) = ChunkPosition::from_tile(IVec2::ZERO, &config);
+    let mut chunk = Chunk::filled(config.chunk_tile_count, registry.find("water").unwrap());
+    chunk.set(local, registry.find("grass").unwrap());
+    let chunk = app.world.spawn(chunk).id();
+    app.world.resource_mut::<Map>().insert(position, chunk);
+
+    let state = |app: &mut App| {
+        app.update();
+        app.world
+            .query::<&Player>()
+            .single(&app.world)
+            .movement_state()
+    };
+
+    app.world.resource_mut::<MovementInput>().0 = Vec2::X;
+    assert_eq!(state(&mut app), MovementState::Walk);
+
+    let mut frames = 0;
+    while state(&mut app) != MovementState::Swim {
+        frames += 1;
+        assert!(frames < 100, "the player should walk into the water");
+    }
+}
@@ -0,0 +1,150 @@
+//! Tests for loading tiles from data and drawing them.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::tile_registry::{TileDef, TileId, TileRegistry, TILES_PATH};
+use bevy_game::map::{ChunkBorderState, ChunkLod};
+use image::{Rgba, RgbaImage};
+
+fn tile(name: &str, color: Color, height: Option<f64>) -> TileDef {
+    TileDef {
+        name: name.to_string(),
+        color,
+        land: true,
+        walkable: true,
+        speed: 1.0,
+        swimming: false,
+        texture: None,
+        height,
+    }
+}
+
+#[test]
+fn shipped_tiles_load() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(TILES_PATH);
+    let registry = TileRegistry::load(&path).unwrap();
+
+    assert_eq!(
+        registry.iter().count(),
+        TileRegistry::default().iter().count()
+    );
+}
+
+#[test]
+fn default_terrain_heights() {
+    let registry = TileRegistry::default();
+    let name = |height| &registry.get(registry.terrain(height)).unwrap().name;
+
+    assert_eq!(name(0.9), "high_grass");
+    assert_eq!(name(0.4), "grass");
+    assert_eq!(name(0.2), "grass");
+    assert_eq!(name(0.05), "sand");
+    assert_eq!(name(0.0), "water");
+    assert_eq!(name(-0.5), "deep_water");
+    assert_eq!(name(-2.0), "deep_water");
+
+    let water = registry.get(registry.find("Water").unwrap()).unwrap();
+    assert!(!water.land);
+    assert!(water.walkable);
+}
+
+#[test]
+fn tiles_can_be_added_in_data() {
+    let registry = TileRegistry::from_ron(
+        r#"#![enable(implicit_some)]
+        [
+            (name: "water", colour: "2600FE", land: false, height: -1.0),
+            (name: "grass", colour: "54BE44", speed: 0.8, height: 0.0),
+            (name: "snow", colour: "FFFFFF", speed: 0.5, height: 0.6),
+            (name: "road", colour: "808080", speed: 1.5),
+            (name: "wall", colour: "303030", walkable: false),
+        ]"#,
+        Path::new(""),
+    )
+    .unwrap();
+
+    let snow = registry.find("snow").unwrap();
+    assert_eq!(snow, TileId(2));
+    assert_eq!(registry.terrain(0.7), snow);
+    assert_eq!(registry.terrain(0.3), registry.find("grass").unwrap());
+
+    // Tiles without a height are never generated.
+    let road = registry.find("road").unwrap();
+    assert!((-10..=10).all(|height| registry.terrain(f64::from(height) / 10.0) != road));
+
+    let road = registry.get(road).unwrap();
+    assert!(road.land && road.walkable);
+    assert!((road.speed - 1.5).abs() < f32::EPSILON);
+    assert!(
+        !registry
+            .get(registry.find("wall").unwrap())
+            .unwrap()
+            .walkable
+    );
+}
+
+#[test]
+fn invalid_tiles_are_rejected() {
+    let parse = |data| TileRegistry::from_ron(data, Path::new(""));
+
+    assert!(parse("[(name: \"a\", colour: \"nope\", height: Some(0.0))]").is_err());
+    assert!(parse("[(name: \"a\", colour: \"FFFFFF\")]").is_err());
+    assert!(parse(
+        "[(name: \"a\", colour: \"FFFFFF\", height: Some(0.0)), (name: \"A\", colour: \"000000\")]"
+    )
+    .is_err());
+    assert!(parse("[(name: \"a\", colour: \"FFFFFF\", texture: Some(\"missing.png\"))]").is_err());
+    assert!(TileRegistry::new(vec![tile("a", Color::WHITE, Some(0.0)); 257]).is_err());
+}
+
+#[test]
+fn textures_are_drawn_over_large_tiles() {
+    let (red, blue) = (Rgba([255, 0, 0, 255]), Rgba([0, 0, 255, 255]));
+    let checker = RgbaImage::from_fn(2, 2, |x, y| if (x + y) % 2 == 0 { red } else { blue });
+
+    let registry = TileRegistry::new(vec![
+        tile("plain", Color::WHITE, Some(0.0)),
+        TileDef {
+            texture: Some(checker),
+            ..tile("checker", Color::GREEN, None)
+        },
+    ])
+    .unwrap();
+
+    let config = MapConfig {
+        tile_size: 4.0,
+        chunk_tile_count: 2,
+        ..MapConfig::default()
+    };
+    let mut chunk = Chunk::filled(2, TileId(0));
+    // Top left of the chunk, as rows go from the bottom.
+    chunk.set(UVec2::new(0, 1), TileId(1));
+
+    let image = chunk
+        .generate_texture_image(&config, &registry, ChunkBorderState::Hidden, ChunkLod(0))
+        .try_into_dynamic()
+        .unwrap()
+        .to_rgba8();
+
+    assert_eq!(*image.get_pixel(0, 0), red);
+    assert_eq!(*image.get_pixel(1, 1), red);
+    assert_eq!(*image.get_pixel(2, 0), blue);
+    assert_eq!(*image.get_pixel(0, 2), blue);
+    assert_eq!(*image.get_pixel(4, 0), Rgba([255, 255, 255, 255]));
+
+    // Single pixel tiles are too small for textures, so they use the colour.
+    let config = MapConfig {
+        pixel_per_tile: true,
+        ..config
+    };
+    let image = chunk
+        .generate_texture_image(&config, &registry, ChunkBorderState::Hidden, ChunkLod(0))
+        .try_into_dynamic()
+        .unwrap()
+        .to_rgba8();
+
+    assert_eq!(*image.get_pixel(0, 0), Rgba(Color::GREEN.as_rgba_u8()));
+}
@@ -0,0 +1,99 @@
+//! Tests for exporting generated regions as Tiled maps.
+
+#![allow(clippy::cast_sign_loss)]
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::noise::NoiseMap;
+use bevy_game::map::region::{region_tiled_map, ChunkRect};
+use bevy_game::map::stamp::Stamp;
+use bevy_game::map::tile_registry::TileRegistry;
+use bevy_game::map::tiled::{tileset_image, TiledMap};
+
+fn config() -> MapConfig {
+    MapConfig {
+        seed: 7,
+        chunk_tile_count: 10,
+        ..MapConfig::default()
+    }
+}
+
+#[test]
+fn exported_maps_match_generated_chunks() {
+    let config = config();
+    let registry = TileRegistry::default();
+    let noisemap = NoiseMap::new(&config);
+    let rect = ChunkRect::new(ChunkPosition { x: -1, y: 0 }, ChunkPosition { x: 1, y: 1 });
+
+    let map = region_tiled_map(&config, &registry, rect);
+    assert_eq!(map.size, UVec2::new(30, 20));
+
+    for position in rect.positions() {
+        let chunk = Chunk::new(position, &noisemap, &config, &registry);
+        let left = (position.x - rect.min.x) as u32 * 10;
+        let top = (rect.max.y - position.y) as u32 * 10;
+
+        for (local, tile) in chunk.iter() {
+            assert_eq!(
+                map.get(left + local.x, top + 9 - local.y),
+                Some(u32::from(tile.0))
+            );
+        }
+    }
+}
+
+#[test]
+fn exported_maps_parse_back() {
+    let config = config();
+    let registry = TileRegistry::default();
+    let rect = ChunkRect::new(ChunkPosition { x: 0, y: 0 }, ChunkPosition { x: 1, y: 0 });
+
+    let mut map = region_tiled_map(&config, &registry, rect);
+    // Hand edited empty tiles are kept.
+    map.tiles[3] = None;
+
+    let tmx = map.to_tmx(&registry, "town_tiles.png", 16);
+    assert!(tmx.contains(r#"<image source="town_tiles.png" width="80" height="16"/>"#));
+    assert!(tmx.contains(r#"<property name="name" value="deep_water"/>"#));
+
+    assert_eq!(TiledMap::parse(&tmx).unwrap(), map);
+}
+
+#[test]
+fn exported_maps_stamp_back_unchanged() {
+    let registry = TileRegistry::default();
+    let rect = ChunkRect::new(ChunkPosition { x: 2, y: -1 }, ChunkPosition { x: 3, y: 0 });
+    let map = region_tiled_map(&config(), &registry, rect);
+
+    // Stamped into a different world, the region looks like the one it was exported from.
+    let stamp = Stamp::from_tiled(&map, rect.min, &HashMap::new(), &registry).unwrap();
+    let stamped = MapConfig {
+        seed: 8,
+        stamps: vec![stamp],
+        ..config()
+    };
+    let noisemap = NoiseMap::new(&config());
+    let stamped_noisemap = NoiseMap::new(&stamped);
+
+    for position in rect.positions() {
+        assert_eq!(
+            Chunk::new(position, &stamped_noisemap, &stamped, &registry),
+            Chunk::new(position, &noisemap, &config(), &registry)
+        );
+    }
+}
+
+#[test]
+fn tileset_has_tile_colours() {
+    let registry = TileRegistry::default();
+    let image = tileset_image(&registry, 4);
+
+    assert_eq!(image.dimensions(), (5 * 4, 4));
+    for (id, tile) in registry.iter() {
+        let x = u32::from(id.0) * 4;
+        assert_eq!(image.get_pixel(x + 3, 2).0, tile.color.as_rgba_u8());
+    }
+}
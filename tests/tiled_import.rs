@@ -0,0 +1,167 @@
+//! Tests for placing Tiled maps into the world as stamps.
+
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_game::map::chunk::Chunk;
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::noise::NoiseMap;
+use bevy_game::map::stamp::{load_stamps, Stamp, STAMPS_PATH};
+use bevy_game::map::tile_registry::{TileId, TileRegistry};
+use bevy_game::map::tiled::TiledMap;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tiled")
+        .join(name)
+}
+
+/// The tiles of the fixture maps, rows from the top.
+const TOWN: [Option<u32>; 12] = [
+    Some(0),
+    Some(0),
+    Some(1),
+    Some(4),
+    Some(0),
+    Some(2),
+    Some(2),
+    Some(1),
+    None,
+    Some(3),
+    Some(0),
+    Some(0),
+];
+
+#[test]
+fn maps_are_parsed_and_layers_merged() {
+    for name in ["town.tmx", "town_zlib.tmx"] {
+        let map = TiledMap::load(&fixture(name)).unwrap();
+
+        assert_eq!(map.size, UVec2::new(4, 3), "{name}");
+        assert_eq!(map.tiles, TOWN, "{name}");
+    }
+}
+
+#[test]
+fn unsupported_maps_are_rejected() {
+    assert!(TiledMap::parse("<map infinite=\"1\" width=\"1\" height=\"1\"></map>").is_err());
+    assert!(TiledMap::parse(
+        "<map width=\"2\" height=\"1\"><tileset firstgid=\"1\"/><layer><data \
+         encoding=\"csv\">1</data></layer></map>"
+    )
+    .is_err());
+    assert!(TiledMap::parse("<layer/>").is_err());
+    // Tile ids within each tileset would collide.
+    assert!(TiledMap::parse(
+        "<map width=\"1\" height=\"1\"><tileset firstgid=\"1\"/><tileset firstgid=\"9\"/><layer><data \
+         encoding=\"csv\">9</data></layer></map>"
+    )
+    .is_err());
+    assert!(TiledMap::parse(
+        "<map width=\"1\" height=\"1\"><tileset firstgid=\"1\"/><layer><data><tile \
+         gid=\"1\"/></data></layer></map>"
+    )
+    .is_err());
+}
+
+#[test]
+fn stamps_replace_generated_tiles() {
+    let registry = TileRegistry::default();
+    let map = TiledMap::load(&fixture("town.tmx")).unwrap();
+    let mapping = HashMap::from([(0, registry.find("sand").unwrap())]);
+
+    // Small chunks so the stamp covers parts of four chunks.
+    let stamp_position = ChunkPosition { x: 2, y: 1 };
+    let config = MapConfig {
+        seed: 42,
+        chunk_tile_count: 3,
+        stamps: vec![Stamp::from_tiled(&map, stamp_position, &mapping, &registry).unwrap()],
+        ..MapConfig::default()
+    };
+    let noisemap = NoiseMap::new(&config);
+    let unstamped = MapConfig {
+        stamps: Vec::new(),
+        ..config.clone()
+    };
+
+    let first_tile = stamp_position.first_tile(&config);
+    for y in 0..3 {
+        for x in 0..4 {
+            // Tiled rows go from the top.
+            let tile = first_tile + IVec2::new(x, 2 - y);
+            let (position, local) = ChunkPosition::from_tile(tile, &config);
+            let stamped = Chunk::new(position, &noisemap, &config, &registry).get(local);
+            let generated = Chunk::new(position, &noisemap, &unstamped, &registry).get(local);
+
+            let expected = match TOWN[(y * 4 + x) as usize] {
+                Some(0) => registry.find("sand"),
+                Some(id) => Some(TileId(id as u8)),
+                None => generated,
+            };
+            assert_eq!(stamped, expected, "tile {tile}");
+        }
+    }
+
+    // Tiles around the stamp are left alone.
+    for tile in [first_tile - IVec2::ONE, first_tile + IVec2::new(4, 0)] {
+        let (position, local) = ChunkPosition::from_tile(tile, &config);
+        assert_eq!(
+            Chunk::new(position, &noisemap, &config, &registry).get(local),
+            Chunk::new(position, &noisemap, &unstamped, &registry).get(local),
+        );
+    }
+}
+
+#[test]
+fn unknown_tiles_are_rejected() {
+    let registry = TileRegistry::default();
+    let map = TiledMap {
+        size:  UVec2::ONE,
+        tiles: vec![Some(200)],
+    };
+
+    assert!(Stamp::from_tiled(
+        &map,
+        ChunkPosition { x: 0, y: 0 },
+        &HashMap::new(),
+        &registry
+    )
+    .is_err());
+}
+
+#[test]
+fn stamps_load_from_file() {
+    let registry = TileRegistry::default();
+
+    let shipped = Path::new(env!("CARGO_MANIFEST_DIR")).join(STAMPS_PATH);
+    assert_eq!(load_stamps(&shipped, &registry).unwrap(), Vec::new());
+
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("stamps.ron");
+    std::fs::write(
+        &path,
+        format!(
+            "[(map: {:?}, position: (x: -1, y: 4), tiles: {{0: \"grass\"}})]",
+            fixture("town.tmx")
+        ),
+    )
+    .unwrap();
+
+    let stamps = load_stamps(&path, &registry).unwrap();
+    assert_eq!(stamps.len(), 1);
+    assert_eq!(stamps[0].position, ChunkPosition { x: -1, y: 4 });
+    assert_eq!(stamps[0].size(), UVec2::new(4, 3));
+
+    std::fs::write(
+        &path,
+        format!(
+            "[(map: {:?}, position: (x: 0, y: 0), tiles: {{0: \"lava\"}})]",
+            fixture("town.tmx")
+        ),
+    )
+    .unwrap();
+    assert!(load_stamps(&path, &registry).is_err());
+}
@@ -0,0 +1,198 @@
+//! Tests for island and wrapped world shapes.
+
+#![allow(clippy::cast_possible_wrap)]
+#![allow(clippy::cast_precision_loss)]
+
+use bevy::prelude::*;
+use bevy_game::map::chunk_position::ChunkPosition;
+use bevy_game::map::config::MapConfig;
+use bevy_game::map::noise::NoiseMap;
+use bevy_game::map::tile_registry::TileRegistry;
+use bevy_game::map::world_shape::{Falloff, WorldShape};
+
+const RADIUS: u32 = 100;
+
+fn island(falloff: Falloff) -> MapConfig {
+    MapConfig {
+        seed: 42,
+        world_shape: WorldShape::Island {
+            radius: RADIUS,
+            falloff,
+        },
+        ..MapConfig::default()
+    }
+}
+
+#[test]
+fn islands_are_surrounded_by_deep_water() {
+    let registry = TileRegistry::default();
+    let deep_water = registry.find("deep_water").unwrap();
+    let radius = RADIUS as i32;
+
+    for falloff in [Falloff::Radial, Falloff::Square] {
+        let noisemap = NoiseMap::new(&island(falloff));
+
+        for tile in [
+            IVec2::new(radius, 0),
+            IVec2::new(0, -radius - 1),
+            IVec2::new(radius * 3, radius * 2),
+            IVec2::new(-radius * 10, 7),
+        ] {
+            assert_eq!(
+                registry.terrain(noisemap.sample(tile)),
+                deep_water,
+                "{falloff:?} tile {tile}"
+            );
+        }
+    }
+
+    // Square islands reach into the corners, radial ones dont.
+    let corner = IVec2::new(-radius * 13 / 20, radius * 13 / 20);
+    let radial = NoiseMap::new(&island(Falloff::Radial));
+    let square = NoiseMap::new(&island(Falloff::Square));
+    assert_eq!(registry.terrain(radial.sample(corner)), deep_water);
+    let square_tile = registry.get(registry.terrain(square.sample(corner)));
+    assert!(square_tile.is_some_and(|tile| tile.land));
+}
+
+#[test]
+fn island_centre_matches_infinite_terrain() {
+    let infinite = NoiseMap::new(&MapConfig {
+        seed: 42,
+        ..MapConfig::default()
+    });
+    let island = NoiseMap::new(&island(Falloff::Radial));
+
+    for tile in [IVec2::ZERO, IVec2::new(10, -20), IVec2::new(-30, 25)] {
+        assert_eq!(
+            island.sample(tile).to_bits(),
+            infinite.sample(tile).to_bits()
+        );
+    }
+}
+
+#[test]
+fn positions_are_clamped_to_the_island() {
+    let tile_size = 10.0;
+    let edge = RADIUS as f32 * tile_size;
+
+    let radial = WorldShape::Island {
+        radius:  RADIUS,
+        falloff: Falloff::Radial,
+    };
+    let square = WorldShape::Island {
+        radius:  RADIUS,
+        falloff: Falloff::Square,
+    };
+
+    let outside = Vec2::new(edge * 2.0, edge * 2.0);
+    assert!((radial.clamp(outside, tile_size).length() - edge).abs() < 0.01);
+    assert_eq!(square.clamp(outside, tile_size), Vec2::splat(edge));
+
+    let inside = Vec2::new(edge / 2.0, -edge / 2.0);
+    assert_eq!(radial.clamp(inside, tile_size), inside);
+    assert_eq!(square.clamp(inside, tile_size), inside);
+    assert_eq!(WorldShape::Infinite.clamp(outside, tile_size), outside);
+}
+
+fn wrapped(chunk_tile_count: usize) -> MapConfig {
+    MapConfig {
+        seed: 42,
+        chunk_tile_count,
+        world_shape: WorldShape::Wrapped {
+            width:  5,
+            height: 3,
+        },
+        ..MapConfig::default()
+    }
+}
+
+#[test]
+fn wrapped_worlds_repeat() {
+    let config = wrapped(20);
+    let noisemap = NoiseMap::new(&config);
+    let area = config
+        .world_shape
+        .wrap_area(config.chunk_tile_count)
+        .unwrap();
+
+    assert_eq!(area.size(), IVec2::new(100, 60));
+    assert_eq!(area.min, IVec2::new(-40, -20));
+
+    for tile in [
+        IVec2::ZERO,
+        IVec2::new(-40, -20),
+        IVec2::new(59, 39),
+        IVec2::new(13, -7),
+    ] {
+        for repeat in [IVec2::new(1, 0), IVec2::new(0, -1), IVec2::new(-3, 2)] {
+            let repeated = tile + repeat * area.size();
+            assert_eq!(
+                noisemap.sample(repeated).to_bits(),
+                noisemap.sample(tile).to_bits(),
+                "tile {tile} repeated at {repeated}"
+            );
+            assert_eq!(
+                config
+                    .world_shape
+                    .wrap_tile(repeated, config.chunk_tile_count),
+                tile
+            );
+        }
+    }
+
+    let chunk = ChunkPosition { x: 2, y: 1 };
+    assert_eq!(ChunkPosition { x: 7, y: -2 }.wrap(&config), chunk);
+    assert_eq!(chunk.wrap(&config), chunk);
+}
+
+#[test]
+fn wrapped_seams_are_continuous() {
+    let config = wrapped(20);
+    let noisemap = NoiseMap::new(&config);
+    let area = config
+        .world_shape
+        .wrap_area(config.chunk_tile_count)
+        .unwrap();
+
+    // The biggest jump between neighbouring tiles away from the seams.
+    let largest_step = (area.min.y..area.max.y)
+        .flat_map(|y| (area.min.x..area.max.x - 1).map(move |x| IVec2::new(x, y)))
+        .map(|tile| (noisemap.sample(tile + IVec2::X) - noisemap.sample(tile)).abs())
+        .fold(0.0, f64::max);
+
+    for y in area.min.y..area.max.y {
+        let step = (noisemap.sample(IVec2::new(area.max.x, y))
+            - noisemap.sample(IVec2::new(area.max.x - 1, y)))
+        .abs();
+        assert!(
+            step <= largest_step * 1.5,
+            "seam at row {y} jumps by {step}"
+        );
+    }
+
+    for x in area.min.x..area.max.x {
+        let step = (noisemap.sample(IVec2::new(x, area.max.y))
+            - noisemap.sample(IVec2::new(x, area.max.y - 1)))
+        .abs();
+        assert!(
+            step <= largest_step * 1.5,
+            "seam at column {x} jumps by {step}"
+        );
+    }
+}
+
+#[test]
+fn walking_off_a_wrapped_world_teleports() {
+    let config = wrapped(20);
+    let width = 100.0 * config.tile_size;
+    let left_edge = -40.0 * config.tile_size;
+
+    let position = Vec2::new(left_edge - 1.0, 5.0);
+    let wrapped = config.world_shape.wrap(position, &config);
+    assert!((wrapped.x - (position.x + width)).abs() < 0.01);
+    assert!((wrapped.y - position.y).abs() < 0.01);
+
+    let inside = Vec2::new(10.0, -10.0);
+    assert_eq!(config.world_shape.wrap(inside, &config), inside);
+}